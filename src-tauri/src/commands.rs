@@ -1,60 +1,263 @@
+use std::path::Path;
 use std::sync::Mutex;
 
 use tauri::State;
 
-use crate::game::game_engine::GameEngine;
-use crate::models::game_state::GameState;
+use crate::game::game_engine::{random_seed, GameEngine, HandSetup};
+use crate::game::match_engine::MatchEngine;
+use crate::game::replay::{ReplayEngine, ReplayFrame, ReplaySeek};
+use crate::game::save::SaveFile;
+use crate::game::shanten::DiscardOption;
+use crate::models::event::GameEvent;
+use crate::models::game_state::{GameState, Player};
+use crate::models::match_state::MatchLength;
+use crate::models::player_view::PlayerView;
+use crate::models::record::{Action, GameRecord};
+use crate::models::rules::RuleConfig;
+use crate::models::tile::Tile;
 
 pub struct SharedState {
     pub engine: Mutex<GameEngine>,
+    pub match_engine: Mutex<MatchEngine>,
+    /// Recorded game being reviewed, separate from the game in play
+    pub replay: Mutex<Option<ReplayEngine>>,
+}
+
+/// What the frontend is shown: the game from the human player's seat
+fn player_view(state: &GameState) -> PlayerView {
+    PlayerView::new(state, Player::Player)
+}
+
+#[tauri::command]
+pub fn new_game(state: State<SharedState>, seed: Option<u64>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let mut match_engine = state.match_engine.lock().map_err(|e| e.to_string())?;
+    // A standalone hand leaves any running match
+    *match_engine = MatchEngine::new();
+    let game_state = engine.new_game(seed);
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn new_game_with_rules(
+    state: State<SharedState>,
+    rules: RuleConfig,
+    seed: Option<u64>,
+) -> Result<PlayerView, String> {
+    rules.validate()?;
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let mut match_engine = state.match_engine.lock().map_err(|e| e.to_string())?;
+    // A standalone hand under `rules`; matches are started with `start_match`
+    *match_engine = MatchEngine::new();
+    let game_state = engine.start_hand(HandSetup {
+        seed: Some(seed.unwrap_or_else(random_seed)),
+        ..HandSetup::with_rules(rules)
+    });
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn start_match(state: State<SharedState>, length: MatchLength, seed: Option<u64>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let mut match_engine = state.match_engine.lock().map_err(|e| e.to_string())?;
+    let rules = RuleConfig {
+        match_length: length,
+        ..RuleConfig::default()
+    };
+    let game_state = match_engine.start_match(&mut engine, rules, seed);
+    Ok(player_view(game_state))
 }
 
 #[tauri::command]
-pub fn new_game(state: State<SharedState>) -> Result<GameState, String> {
+pub fn next_hand(state: State<SharedState>) -> Result<PlayerView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    let game_state = engine.new_game().clone();
-    Ok(game_state)
+    let mut match_engine = state.match_engine.lock().map_err(|e| e.to_string())?;
+    let game_state = match_engine.next_hand(&mut engine)?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn get_state(state: State<SharedState>) -> Result<PlayerView, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    Ok(engine.view(Player::Player))
+}
+
+/// Events from index `since` on, as the player may see them
+#[tauri::command]
+pub fn get_events(state: State<SharedState>, since: usize) -> Result<Vec<GameEvent>, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let events = engine.events_since(since).iter().map(|event| event.masked_for(Player::Player)).collect();
+    Ok(events)
 }
 
+/// Full unmasked state, every hand included; for the debug panel only
 #[tauri::command]
-pub fn get_state(state: State<SharedState>) -> Result<GameState, String> {
+pub fn get_debug_state(state: State<SharedState>) -> Result<GameState, String> {
     let engine = state.engine.lock().map_err(|e| e.to_string())?;
     Ok(engine.get_state())
 }
 
 #[tauri::command]
-pub fn player_draw(state: State<SharedState>) -> Result<GameState, String> {
+pub fn player_draw(state: State<SharedState>) -> Result<PlayerView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    let game_state = engine.player_draw()?.clone();
-    Ok(game_state)
+    let game_state = engine.apply(Action::PlayerDraw)?;
+    Ok(player_view(game_state))
 }
 
 #[tauri::command]
-pub fn player_discard(state: State<SharedState>, tile_id: u8) -> Result<GameState, String> {
+pub fn player_discard(state: State<SharedState>, tile: Tile) -> Result<PlayerView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    let game_state = engine.player_discard(tile_id)?.clone();
-    Ok(game_state)
+    let game_state = engine.apply(Action::PlayerDiscard { tile })?;
+    Ok(player_view(game_state))
 }
 
 #[tauri::command]
-pub fn cpu_step(state: State<SharedState>) -> Result<GameState, String> {
+pub fn cpu_step(state: State<SharedState>) -> Result<PlayerView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    let game_state = engine.cpu_step()?.clone();
-    Ok(game_state)
+    let game_state = engine.apply(Action::CpuStep)?;
+    Ok(player_view(game_state))
 }
 
 #[tauri::command]
-pub fn player_ron(state: State<SharedState>) -> Result<GameState, String> {
+pub fn player_ron(state: State<SharedState>) -> Result<PlayerView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    let game_state = engine.player_ron()?.clone();
-    Ok(game_state)
+    let game_state = engine.apply(Action::PlayerRon)?;
+    Ok(player_view(game_state))
 }
 
 #[tauri::command]
-pub fn player_pass(state: State<SharedState>) -> Result<GameState, String> {
+pub fn player_pass(state: State<SharedState>) -> Result<PlayerView, String> {
     let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
-    let game_state = engine.player_pass()?.clone();
-    Ok(game_state)
+    let game_state = engine.apply(Action::PlayerPass)?;
+    Ok(player_view(game_state))
 }
 
+#[tauri::command]
+pub fn player_tsumo(state: State<SharedState>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerTsumo)?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn player_pon(state: State<SharedState>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerPon)?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn player_chi(state: State<SharedState>, tiles: Vec<Tile>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerChi { tiles })?;
+    Ok(player_view(game_state))
+}
 
+#[tauri::command]
+pub fn player_daiminkan(state: State<SharedState>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerDaiminkan)?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn player_kan(state: State<SharedState>, tile: Tile) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerKan { tile })?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn player_riichi(state: State<SharedState>, tile: Tile) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerRiichi { tile })?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn player_kyuushu(state: State<SharedState>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerKyuushu)?;
+    Ok(player_view(game_state))
+}
+
+#[tauri::command]
+pub fn player_open_riichi(state: State<SharedState>, tile: Tile) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.apply(Action::PlayerOpenRiichi { tile })?;
+    Ok(player_view(game_state))
+}
+
+/// How far the player's hand is from tenpai: 0 is tenpai, -1 a complete hand
+#[tauri::command]
+pub fn get_shanten(state: State<SharedState>) -> Result<i8, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    Ok(engine.shanten(Player::Player))
+}
+
+/// Each discard the player could make, best first, with the shanten and improving tiles it leaves
+#[tauri::command]
+pub fn analyze_discards(state: State<SharedState>) -> Result<Vec<DiscardOption>, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    engine.analyze_discards(Player::Player)
+}
+
+/// Take back the player's last discard (practice mode only)
+#[tauri::command]
+pub fn undo(state: State<SharedState>) -> Result<PlayerView, String> {
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let game_state = engine.undo()?;
+    Ok(player_view(game_state))
+}
+
+/// Seed, rules and actions of the finished game, for replaying it
+#[tauri::command]
+pub fn get_record(state: State<SharedState>) -> Result<GameRecord, String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    engine.finished_record().cloned()
+}
+
+#[tauri::command]
+pub fn replay_load(state: State<SharedState>, record: GameRecord) -> Result<ReplayFrame, String> {
+    let mut replay = state.replay.lock().map_err(|e| e.to_string())?;
+    let loaded = ReplayEngine::load(record)?;
+    let frame = loaded.frame();
+    *replay = Some(loaded);
+    Ok(frame)
+}
+
+#[tauri::command]
+pub fn replay_seek(state: State<SharedState>, seek: ReplaySeek) -> Result<ReplayFrame, String> {
+    let mut replay = state.replay.lock().map_err(|e| e.to_string())?;
+    let replay = replay.as_mut().ok_or("No replay loaded")?;
+    replay.seek(seek)
+}
+
+/// Write the game in progress, match included, to a save file at `path`
+pub fn save_to(state: &SharedState, path: &Path) -> Result<(), String> {
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let match_engine = state.match_engine.lock().map_err(|e| e.to_string())?;
+    SaveFile::new(&engine, &match_engine).write(path)
+}
+
+/// Replace the game in progress with the one saved at `path`
+pub fn load_from(state: &SharedState, path: &Path) -> Result<(), String> {
+    let save = SaveFile::read(path)?;
+    let mut engine = state.engine.lock().map_err(|e| e.to_string())?;
+    let mut match_engine = state.match_engine.lock().map_err(|e| e.to_string())?;
+    *engine = save.engine;
+    *match_engine = save.match_engine;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn save_game(state: State<SharedState>, path: String) -> Result<(), String> {
+    save_to(&state, Path::new(&path))
+}
+
+#[tauri::command]
+pub fn load_game(state: State<SharedState>, path: String) -> Result<PlayerView, String> {
+    load_from(&state, Path::new(&path))?;
+    let engine = state.engine.lock().map_err(|e| e.to_string())?;
+    Ok(engine.view(Player::Player))
+}
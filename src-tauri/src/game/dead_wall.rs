@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::scoring::DoraCount;
+use crate::models::tile::Tile;
+
+/// Number of tiles kept out of the live wall
+pub const DEAD_WALL_SIZE: usize = 14;
+
+/// Replacement tiles available for kans
+const RINSHAN_SIZE: usize = 4;
+
+/// The 14 tiles set aside at the start of a hand: kan replacement tiles and dora indicators
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeadWall {
+    /// Replacement tiles, drawn from the end after a kan
+    rinshan: Vec<Tile>,
+    /// Dora indicators in reveal order
+    indicators: Vec<Tile>,
+    /// Ura-dora indicators lying under each dora indicator
+    ura_indicators: Vec<Tile>,
+    /// Number of dora indicators turned face up
+    revealed: usize,
+}
+
+impl DeadWall {
+    /// Split the dead wall off the far end of the wall (the live wall is drawn with `pop`)
+    /// and turn over the first dora indicator.
+    pub fn split_from(wall: &mut Vec<Tile>) -> Self {
+        let count = DEAD_WALL_SIZE.min(wall.len());
+        let mut tiles: Vec<Tile> = wall.drain(..count).collect();
+        let indicator_count = tiles.len().saturating_sub(RINSHAN_SIZE) / 2;
+        let ura_indicators = tiles.split_off(tiles.len() - indicator_count);
+        let indicators = tiles.split_off(tiles.len() - indicator_count);
+        DeadWall {
+            rinshan: tiles,
+            indicators,
+            ura_indicators,
+            revealed: 1.min(indicator_count),
+        }
+    }
+
+    /// Another kan may be declared while an indicator is left to reveal
+    pub fn can_kan(&self) -> bool {
+        self.revealed < self.indicators.len() && !self.rinshan.is_empty()
+    }
+
+    /// Draw the replacement tile for a kan and reveal a new dora indicator.
+    /// The last tile of the live wall moves over so the dead wall stays at 14 tiles.
+    pub fn draw_replacement(&mut self, wall: &mut Vec<Tile>) -> Option<Tile> {
+        if !self.can_kan() {
+            return None;
+        }
+        let tile = self.rinshan.pop()?;
+        if !wall.is_empty() {
+            self.rinshan.insert(0, wall.remove(0));
+        }
+        self.revealed += 1;
+        Some(tile)
+    }
+
+    /// Dora indicators turned face up so far
+    pub fn dora_indicators(&self) -> &[Tile] {
+        &self.indicators[..self.revealed]
+    }
+
+    /// Ura-dora indicators under the revealed dora indicators
+    pub fn ura_dora_indicators(&self) -> &[Tile] {
+        &self.ura_indicators[..self.revealed]
+    }
+
+    /// Dora among `tiles` (the winner's whole hand, melds included); ura-dora only count for riichi
+    pub fn count_dora(&self, tiles: &[Tile], riichi: bool) -> DoraCount {
+        let count = |indicators: &[Tile]| -> u8 {
+            indicators
+                .iter()
+                .map(|indicator| tiles.iter().filter(|t| t.id == dora_from_indicator(indicator.id)).count() as u8)
+                .sum()
+        };
+        DoraCount {
+            dora: count(self.dora_indicators()),
+            ura_dora: if riichi { count(self.ura_dora_indicators()) } else { 0 },
+            aka_dora: tiles.iter().filter(|t| t.red).count() as u8,
+        }
+    }
+
+    /// Number of tiles in the dead wall
+    pub fn len(&self) -> usize {
+        self.rinshan.len() + self.indicators.len() + self.ura_indicators.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Tile id made dora by `indicator`: the next tile of its suit, with winds and dragons cycling on their own
+pub fn dora_from_indicator(indicator: u8) -> u8 {
+    match indicator {
+        0..=26 => indicator - indicator % 9 + (indicator % 9 + 1) % 9,
+        27..=30 => 27 + (indicator - 26) % 4,
+        _ => 31 + (indicator - 30) % 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tile::{create_wall, RedFives};
+
+    #[test]
+    fn test_split_from_wall() {
+        let mut wall = create_wall(RedFives::Three);
+        let dead_wall = DeadWall::split_from(&mut wall);
+        assert_eq!(dead_wall.len(), DEAD_WALL_SIZE);
+        assert_eq!(wall.len(), 136 - DEAD_WALL_SIZE);
+        assert_eq!(dead_wall.dora_indicators().len(), 1);
+    }
+
+    #[test]
+    fn test_replacement_draw_keeps_size() {
+        let mut wall = create_wall(RedFives::Three);
+        let mut dead_wall = DeadWall::split_from(&mut wall);
+        let live = wall.len();
+
+        // Four kans are allowed, each revealing another indicator
+        for kan in 1..=4 {
+            assert!(dead_wall.draw_replacement(&mut wall).is_some());
+            assert_eq!(dead_wall.len(), DEAD_WALL_SIZE);
+            assert_eq!(wall.len(), live - kan);
+            assert_eq!(dead_wall.dora_indicators().len(), kan + 1);
+        }
+        assert!(!dead_wall.can_kan());
+        assert!(dead_wall.draw_replacement(&mut wall).is_none());
+    }
+
+    #[test]
+    fn test_dora_from_indicator() {
+        assert_eq!(dora_from_indicator(0), 1); // 1m -> 2m
+        assert_eq!(dora_from_indicator(8), 0); // 9m -> 1m
+        assert_eq!(dora_from_indicator(17), 9); // 9p -> 1p
+        assert_eq!(dora_from_indicator(30), 27); // North -> East
+        assert_eq!(dora_from_indicator(28), 29); // South -> West
+        assert_eq!(dora_from_indicator(33), 31); // Red -> White
+    }
+
+    #[test]
+    fn test_count_dora() {
+        // Dead wall: rinshan 4x 1m, dora indicators 5x 9m, ura indicators 5x East
+        let mut wall: Vec<Tile> = [0; 4].iter().chain(&[8; 5]).chain(&[27; 5]).map(|&id| Tile::new(id).unwrap()).collect();
+        let dead_wall = DeadWall::split_from(&mut wall);
+        let mut hand: Vec<Tile> = [0, 0, 1, 28, 28, 28].iter().map(|&id| Tile::new(id).unwrap()).collect();
+        hand.push(Tile::red_five(4).unwrap());
+
+        assert_eq!(dead_wall.count_dora(&hand, false), DoraCount { dora: 2, ura_dora: 0, aka_dora: 1 });
+        assert_eq!(dead_wall.count_dora(&hand, true), DoraCount { dora: 2, ura_dora: 3, aka_dora: 1 });
+    }
+}
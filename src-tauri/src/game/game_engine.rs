@@ -1,28 +1,149 @@
-use crate::models::game_state::{GamePhase, GameState, Player};
+use crate::models::game_state::{
+    CallOption, GamePhase, GameResult, GameState, Meld, MeldKind, Player, Wind, WinType, RIICHI_STICK,
+};
+use crate::models::event::GameEvent;
+use crate::models::match_state::MatchState;
+use crate::models::player_view::PlayerView;
+use crate::models::record::{Action, GameRecord};
+use crate::models::rules::{RonResolution, RuleConfig};
 use crate::models::tile::{create_wall, shuffle_wall, sort_hand, Tile};
-use crate::game::win_checker::{can_win, can_win_by_ron};
-use rand::Rng;
+use crate::game::dead_wall::DeadWall;
+use crate::game::scoring::{nagashi_mangan_payments, noten_payments, score_hand};
+use crate::game::shanten::{analyze_discards, hand_shanten, DiscardOption};
+use crate::game::win_checker::{can_win_by_ron, can_win_by_tsumo, count_tiles, tenpai_waits, waits_with_remaining};
+use crate::game::yaku::WinContext;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
+/// A fresh seed, kept to 53 bits so it survives a round trip through a JavaScript number
+pub fn random_seed() -> u64 {
+    rand::random::<u64>() >> 11
+}
+
+/// What a hand starts from: seating, counters and points carried over from earlier hands
+#[derive(Debug, Clone)]
+pub struct HandSetup {
+    pub dealer: Player,
+    pub round_wind: Wind,
+    pub honba: u32,
+    pub riichi_sticks: u32,
+    pub scores: [i32; 4],
+    pub match_state: Option<MatchState>,
+    pub rules: RuleConfig,
+    /// Start a new game: reseed the RNG and clear the event log and record. `None` carries on from the previous hand
+    pub seed: Option<u64>,
+}
+
+impl HandSetup {
+    /// First hand under `rules`: East round, the player dealing, everyone on starting points
+    pub fn with_rules(rules: RuleConfig) -> Self {
+        HandSetup {
+            dealer: Player::Player,
+            round_wind: Wind::East,
+            honba: 0,
+            riichi_sticks: 0,
+            scores: [rules.starting_points; 4],
+            match_state: None,
+            rules,
+            seed: None,
+        }
+    }
+}
+
+impl Default for HandSetup {
+    /// A standalone hand under the default rules
+    fn default() -> Self {
+        HandSetup::with_rules(RuleConfig::default())
+    }
+}
+
+/// Everything a game in progress needs, hidden parts included; serializable so it can be saved
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameEngine {
+    /// Live wall; draws are popped from the end
     wall: Vec<Tile>,
+    dead_wall: DeadWall,
+    /// The current player's drawn tile is a kan replacement
+    rinshan_draw: bool,
+    /// Seat whose riichi discard is still out; the stick is paid once nobody rons it
+    pending_riichi: Option<Player>,
+    /// Seat adding a tile to its pon and that tile, while the other seats may rob the kan
+    chankan: Option<(Player, Tile)>,
+    /// Source of all randomness: the wall shuffle and CPU choices
+    rng: ChaCha8Rng,
+    /// Seed `rng` was last started from
+    seed: u64,
+    /// Everything that happened since the game started, in order
+    events: Vec<GameEvent>,
+    /// Seed, rules and actions applied since the game started
+    record: GameRecord,
+    /// The engine as it was before each of the player's discards this hand, newest last.
+    /// Only kept in practice mode, and not saved.
+    #[serde(skip)]
+    undo_stack: Vec<GameEngine>,
     state: GameState,
 }
 
 impl GameEngine {
     pub fn new() -> Self {
+        let seed = random_seed();
         GameEngine {
             wall: Vec::new(),
+            dead_wall: DeadWall::default(),
+            rinshan_draw: false,
+            pending_riichi: None,
+            chankan: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            seed,
+            events: Vec::new(),
+            record: GameRecord::default(),
+            undo_stack: Vec::new(),
             state: GameState::new(),
         }
     }
 
     /// Start a new game: build wall, shuffle, deal 13 tiles to each player, set phase/player.
-    pub fn new_game(&mut self) -> &GameState {
-        self.wall = create_wall();
-        shuffle_wall(&mut self.wall);
+    /// Without a `seed` a fresh one is picked; either way it is reported in the state.
+    pub fn new_game(&mut self, seed: Option<u64>) -> &GameState {
+        self.start_hand(HandSetup {
+            seed: Some(seed.unwrap_or_else(random_seed)),
+            ..HandSetup::default()
+        })
+    }
+
+    /// Start a hand from `setup`; the dealer draws first.
+    pub fn start_hand(&mut self, setup: HandSetup) -> &GameState {
+        if let Some(seed) = setup.seed {
+            self.rng = ChaCha8Rng::seed_from_u64(seed);
+            self.seed = seed;
+            self.events.clear();
+            self.record = GameRecord {
+                seed,
+                rules: setup.rules,
+                is_match: setup.match_state.is_some(),
+                actions: Vec::new(),
+            };
+        }
+        self.undo_stack.clear();
+        self.chankan = None;
+        self.wall = create_wall(setup.rules.red_fives);
+        shuffle_wall(&mut self.wall, &mut self.rng);
+        self.dead_wall = DeadWall::split_from(&mut self.wall);
+        self.rinshan_draw = false;
+        self.pending_riichi = None;
 
         // Reset state
         self.state = GameState::new();
+        self.state.dealer = setup.dealer;
+        self.state.round_wind = setup.round_wind;
+        self.state.honba = setup.honba;
+        self.state.riichi_sticks = setup.riichi_sticks;
+        self.state.scores = setup.scores;
+        self.state.match_state = setup.match_state;
+        self.state.rules = setup.rules;
+        self.state.seed = self.seed;
+        self.state.dora_indicators = self.dead_wall.dora_indicators().to_vec();
 
         // Deal 13 tiles to each seat (Player, Cpu1, Cpu2, Cpu3)
         for hand in self.state.hands.iter_mut() {
@@ -34,13 +155,23 @@ impl GameEngine {
             sort_hand(hand);
         }
 
-        // First draw for the starting player (store in drawn_tile, not in hand)
+        self.record(GameEvent::Deal {
+            dealer: setup.dealer,
+            round_wind: setup.round_wind,
+            honba: setup.honba,
+        });
+        for indicator in self.dead_wall.dora_indicators().to_vec() {
+            self.record(GameEvent::DoraReveal { indicator });
+        }
+
+        // First draw for the dealer (store in drawn_tile, not in hand)
         if let Some(tile) = self.wall.pop() {
-            self.state.drawn_tile[0] = Some(tile);
+            self.state.drawn_tile[setup.dealer.index()] = Some(tile);
+            self.record(GameEvent::Draw { seat: setup.dealer, tile: Some(tile), rinshan: false });
         }
 
         self.state.wall_count = self.wall.len();
-        self.state.current_player = Player::Player;
+        self.state.current_player = setup.dealer;
         // After initial deal and first draw, player can discard
         self.state.phase = GamePhase::Discard;
         
@@ -54,6 +185,139 @@ impl GameEngine {
         self.state.clone()
     }
 
+    /// The state as `seat` may see it
+    pub fn view(&self, seat: Player) -> PlayerView {
+        PlayerView::new(&self.state, seat)
+    }
+
+    /// Every discard open to `seat` ranked by shanten and ukeire; the seat must be about to discard
+    pub fn analyze_discards(&self, seat: Player) -> Result<Vec<DiscardOption>, String> {
+        let i = seat.index();
+        let mut hand = self.state.hands[i].clone();
+        hand.extend(self.state.drawn_tile[i]);
+        if hand.len() % 3 != 2 {
+            return Err("No discard to analyze".into());
+        }
+        Ok(analyze_discards(&hand, self.state.melds[i].len(), &self.visible_counts(seat)))
+    }
+
+    /// Tiles `seat` can see: its own hand, every pond, called melds and dora indicators.
+    /// A called tile is in both a pond and a meld, so it is only counted once.
+    pub fn visible_counts(&self, seat: Player) -> [u8; 34] {
+        let i = seat.index();
+        let mut tiles = self.state.hands[i].clone();
+        tiles.extend(self.state.drawn_tile[i]);
+        tiles.extend(self.state.discards.iter().flatten());
+        tiles.extend(self.state.dora_indicators.iter());
+        for meld in self.state.melds.iter().flatten() {
+            let mut meld_tiles = meld.tiles.clone();
+            if let Some(pos) = meld.called_tile.and_then(|called| meld_tiles.iter().position(|t| *t == called)) {
+                meld_tiles.remove(pos);
+            }
+            tiles.extend(meld_tiles);
+        }
+        count_tiles(&tiles)
+    }
+
+    /// Shanten of a seat's hand, counting its drawn tile
+    pub fn shanten(&self, seat: Player) -> i8 {
+        let i = seat.index();
+        let mut hand = self.state.hands[i].clone();
+        hand.extend(self.state.drawn_tile[i]);
+        hand_shanten(&hand, &self.state.melds[i])
+    }
+
+    /// Events recorded from index `since` on; empty once `since` is past the end
+    pub fn events_since(&self, since: usize) -> &[GameEvent] {
+        self.events.get(since..).unwrap_or_default()
+    }
+
+    fn record(&mut self, event: GameEvent) {
+        self.events.push(event);
+    }
+
+    /// How the game started and the actions applied since, enough to replay it
+    pub fn game_record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// The record once the hand is over; before that its seed would give away every wall
+    pub fn finished_record(&self) -> Result<&GameRecord, String> {
+        if self.state.phase != GamePhase::End {
+            return Err("The record is only available once the game is over".into());
+        }
+        Ok(&self.record)
+    }
+
+    /// Add an action applied outside `apply` to the record
+    pub fn record_action(&mut self, action: Action) {
+        self.record.actions.push(action);
+    }
+
+    /// Apply `action` and add it to the record once it succeeds.
+    /// `NextHand` belongs to the match engine and is rejected here.
+    pub fn apply(&mut self, action: Action) -> Result<&GameState, String> {
+        let is_discard = matches!(
+            action,
+            Action::PlayerDiscard { .. } | Action::PlayerRiichi { .. } | Action::PlayerOpenRiichi { .. }
+        );
+        let snapshot = (is_discard && self.state.rules.practice).then(|| self.snapshot());
+        match &action {
+            Action::PlayerDraw => self.player_draw()?,
+            Action::PlayerDiscard { tile } => self.player_discard(*tile)?,
+            Action::PlayerRiichi { tile } => self.player_riichi(*tile)?,
+            Action::PlayerOpenRiichi { tile } => self.player_open_riichi(*tile)?,
+            Action::PlayerRon => self.player_ron()?,
+            Action::PlayerTsumo => self.player_tsumo()?,
+            Action::PlayerPon => self.player_pon()?,
+            Action::PlayerChi { tiles } => self.player_chi(tiles)?,
+            Action::PlayerDaiminkan => self.player_daiminkan()?,
+            Action::PlayerKan { tile } => self.player_kan(*tile)?,
+            Action::PlayerKyuushu => self.player_kyuushu()?,
+            Action::PlayerPass => self.player_pass()?,
+            Action::CpuStep => self.cpu_step()?,
+            Action::NextHand => return Err("The next hand is dealt by the match engine".into()),
+        };
+        self.record.actions.push(action);
+        self.undo_stack.extend(snapshot);
+        Ok(&self.state)
+    }
+
+    /// Copy of the engine without its undo history
+    fn snapshot(&mut self) -> GameEngine {
+        let undo_stack = std::mem::take(&mut self.undo_stack);
+        let snapshot = self.clone();
+        self.undo_stack = undo_stack;
+        snapshot
+    }
+
+    /// Take back the player's last discard in practice mode, along with every CPU
+    /// draw, discard and call that followed it. The RNG is rewound too, so the CPUs
+    /// play the same way again if the player repeats the discard.
+    pub fn undo(&mut self) -> Result<&GameState, String> {
+        if !self.state.rules.practice {
+            return Err("Undo is only available in practice mode".into());
+        }
+        let snapshot = self.undo_stack.pop().ok_or("No discard to undo")?;
+        let undo_stack = std::mem::take(&mut self.undo_stack);
+        *self = snapshot;
+        self.undo_stack = undo_stack;
+        Ok(&self.state)
+    }
+
+    /// Record the end of the match on the last hand's state, with final scores
+    pub fn finish_match(&mut self, match_state: MatchState, scores: [i32; 4]) -> &GameState {
+        self.state.riichi_sticks = 0;
+        self.state.scores = scores;
+        self.state.match_state = Some(match_state);
+        &self.state
+    }
+
+    #[cfg(test)]
+    pub(crate) fn state_mut(&mut self) -> &mut GameState {
+        &mut self.state
+    }
+
     /// Player draws a tile; validates turn and phase.
     pub fn player_draw(&mut self) -> Result<&GameState, String> {
         // Validate turn and phase
@@ -68,13 +332,14 @@ impl GameEngine {
         if let Some(tile) = self.wall.pop() {
             self.state.drawn_tile[0] = Some(tile);
             self.state.wall_count = self.wall.len();
+            self.record(GameEvent::Draw { seat: Player::Player, tile: Some(tile), rinshan: false });
             self.state.phase = GamePhase::Discard;
             
             // Update win flags (check if player can tsumo with drawn tile)
             self.update_win_flags();
         } else {
             // Wall exhausted
-            self.state.phase = GamePhase::End;
+            self.finish_exhaustive_draw();
             return Ok(&self.state);
         }
 
@@ -82,7 +347,8 @@ impl GameEngine {
     }
 
     /// Player discards a tile; validates turn, phase, and tile existence.
-    pub fn player_discard(&mut self, tile_id: u8) -> Result<&GameState, String> {
+    /// The exact copy is discarded, so a red five and a plain one are told apart.
+    pub fn player_discard(&mut self, tile: Tile) -> Result<&GameState, String> {
         // Validate turn and phase
         if self.state.current_player != Player::Player {
             return Err("Not player's turn".into());
@@ -91,49 +357,49 @@ impl GameEngine {
             return Err("Not in discard phase".into());
         }
 
-        // Check if discarding the drawn tile
-        if let Some(drawn) = self.state.drawn_tile[0] {
-            if drawn.id == tile_id {
-                // Discard the drawn tile (no sorting needed, hand is already sorted)
-                self.state.discards[0].push(drawn);
-                self.state.drawn_tile[0] = None;
-            } else {
-                // Discard from hand: first add drawn_tile to hand, sort, then discard
-                let hand = &mut self.state.hands[0];
-                hand.push(drawn);
-                sort_hand(hand);
-                self.state.drawn_tile[0] = None;
-                
-                // Now discard the specified tile from hand
-                if let Some(pos) = hand.iter().position(|t| t.id == tile_id) {
-                    let tile = hand.remove(pos);
-                    self.state.discards[0].push(tile);
-                } else {
-                    return Err("Tile not found in hand".into());
-                }
-            }
-        } else {
-            // No drawn tile, discard from hand
-            let hand = &mut self.state.hands[0];
-            if let Some(pos) = hand.iter().position(|t| t.id == tile_id) {
-                let tile = hand.remove(pos);
-                self.state.discards[0].push(tile);
-            } else {
-                return Err("Tile not found in hand".into());
-            }
+        // A riichi hand is locked: only the drawn tile may be discarded
+        let declaring = self.pending_riichi == Some(Player::Player);
+        if self.state.riichi[0] && !declaring && self.state.drawn_tile[0] != Some(tile) {
+            return Err("Riichi hand can only discard the drawn tile".into());
         }
 
-        // Update last_discarder to Player
-        self.state.last_discarder = Some(Player::Player);
+        self.discard(Player::Player, tile)?;
+        Ok(&self.state)
+    }
 
-        // Update win flags (check if any player can ron the discarded tile)
-        // Note: Don't advance current_player yet, as handle_ron_phase will set it
-        self.update_win_flags();
-        
-        // Handle ron phase (check if any player can ron)
-        // This will set current_player to the first player who can ron, or advance to next if none
-        self.handle_ron_phase();
+    /// Player declares riichi, discarding `tile` sideways
+    pub fn player_riichi(&mut self, tile: Tile) -> Result<&GameState, String> {
+        self.riichi_with(tile, false)
+    }
+
+    /// Player declares open riichi, showing the hand; only under the open riichi rule
+    pub fn player_open_riichi(&mut self, tile: Tile) -> Result<&GameState, String> {
+        if !self.state.rules.open_riichi {
+            return Err("Open riichi is not allowed".into());
+        }
+        self.riichi_with(tile, true)
+    }
+
+    fn riichi_with(&mut self, tile: Tile, open: bool) -> Result<&GameState, String> {
+        // Validate turn and phase
+        if self.state.current_player != Player::Player {
+            return Err("Not player's turn".into());
+        }
+        if self.state.phase != GamePhase::Discard {
+            return Err("Not in discard phase".into());
+        }
+        self.check_riichi(Player::Player)?;
+        // Checked up front so a failed discard cannot leave a half-declared riichi
+        if self.state.drawn_tile[0] != Some(tile) && !self.state.hands[0].contains(&tile) {
+            return Err("Tile not found in hand".into());
+        }
+        if !self.riichi_discards(Player::Player).contains(&tile.id) {
+            return Err("Hand is not tenpai after that discard".into());
+        }
 
+        self.state.open_riichi[0] = open;
+        self.declare_riichi(Player::Player);
+        self.discard(Player::Player, tile)?;
         Ok(&self.state)
     }
 
@@ -150,36 +416,95 @@ impl GameEngine {
             return Err("Player cannot ron".into());
         }
 
-        // Player rons: game ends
-        self.state.phase = GamePhase::End;
+        self.resolve_rons(true);
+        Ok(&self.state)
+    }
+
+    /// Player chooses to tsumo (win with the drawn tile)
+    pub fn player_tsumo(&mut self) -> Result<&GameState, String> {
+        // Validate turn and phase
+        if self.state.current_player != Player::Player {
+            return Err("Not player's turn".into());
+        }
+        if self.state.phase != GamePhase::Discard {
+            return Err("Not in discard phase".into());
+        }
+        if !self.state.can_tsumo[0] {
+            return Err("Player cannot tsumo".into());
+        }
+
+        self.declare_tsumo(Player::Player);
+        Ok(&self.state)
+    }
+
+    /// Player calls pon on the last discard
+    pub fn player_pon(&mut self) -> Result<&GameState, String> {
+        self.player_call(MeldKind::Pon, None)
+    }
+
+    /// Player calls chi on the last discard using the two given tiles from hand
+    pub fn player_chi(&mut self, tiles: &[Tile]) -> Result<&GameState, String> {
+        self.player_call(MeldKind::Chi, Some(tiles))
+    }
+
+    /// Player calls an open kan on the last discard
+    pub fn player_daiminkan(&mut self) -> Result<&GameState, String> {
+        self.player_call(MeldKind::Daiminkan, None)
+    }
+
+    /// Player declares a closed kan of `tile`, or adds that exact copy to a pon, and draws a replacement tile
+    pub fn player_kan(&mut self, tile: Tile) -> Result<&GameState, String> {
+        if self.state.current_player != Player::Player {
+            return Err("Not player's turn".into());
+        }
+        if self.state.phase != GamePhase::Discard {
+            return Err("Not in discard phase".into());
+        }
+
+        let option = self.state.kan_options[0]
+            .iter()
+            .find(|option| option.tiles.contains(&tile))
+            .cloned()
+            .ok_or("Player cannot kan")?;
+
+        self.declare_kan(Player::Player, option);
+        Ok(&self.state)
+    }
+
+    /// Player declares kyuushu kyuuhai: nine different terminals and honors on the first draw
+    pub fn player_kyuushu(&mut self) -> Result<&GameState, String> {
+        if self.state.current_player != Player::Player {
+            return Err("Not player's turn".into());
+        }
+        if self.state.phase != GamePhase::Discard {
+            return Err("Not in discard phase".into());
+        }
+        if !self.state.can_kyuushu[0] {
+            return Err("Player cannot declare nine terminals".into());
+        }
+
+        self.abort_hand(WinType::NineTerminals);
         Ok(&self.state)
     }
 
-    /// Player chooses to pass (skip ron)
+    /// Player chooses to pass (skip ron or a call)
     pub fn player_pass(&mut self) -> Result<&GameState, String> {
         // Validate turn and phase
         if self.state.current_player != Player::Player {
             return Err("Not player's turn".into());
         }
+        if self.state.phase == GamePhase::Call {
+            self.state.call_options[0].clear();
+            self.resolve_calls(None);
+            return Ok(&self.state);
+        }
         if self.state.phase != GamePhase::Ron {
             return Err("Not in ron phase".into());
         }
 
-        // Player passes: reset player's ron flag first
-        self.state.can_ron[0] = false;
-        
-        // Update win flags to check if other players can still ron the same discarded tile
-        // (in case multiple players could ron, we need to check the next one)
-        self.update_win_flags();
-        
-        // Ensure player's ron flag remains false after update_win_flags
-        // (player has already passed, so they cannot ron even if they could)
+        // Player passes: any CPU claims are settled without the player
         self.state.can_ron[0] = false;
-        
-        // If another player can ron, set phase to Ron for that player
-        // Otherwise, proceed with Draw phase for next player
-        self.handle_ron_phase();
-        
+        self.resolve_rons(false);
         Ok(&self.state)
     }
 
@@ -204,13 +529,14 @@ impl GameEngine {
                     };
                     self.state.drawn_tile[cpu_index] = Some(tile);
                     self.state.wall_count = self.wall.len();
+                    self.record(GameEvent::Draw { seat: self.state.current_player, tile: Some(tile), rinshan: false });
                     self.state.phase = GamePhase::Discard;
                     
                     // Update win flags (check if CPU can tsumo with drawn tile)
                     self.update_win_flags();
                 } else {
                     // Wall exhausted
-                    self.state.phase = GamePhase::End;
+                    self.finish_exhaustive_draw();
                     return Ok(&self.state);
                 }
             }
@@ -223,66 +549,54 @@ impl GameEngine {
                     Player::Player => unreachable!(),
                 };
                 
-                // Add drawn_tile to hand if present, then discard randomly
-                let hand = &mut self.state.hands[cpu_index];
-                if let Some(drawn) = self.state.drawn_tile[cpu_index] {
-                    hand.push(drawn);
-                    self.state.drawn_tile[cpu_index] = None;
-                }
-                
-                if hand.is_empty() {
-                    return Err("CPU hand is empty".into());
+                // CPU always declares tsumo when the drawn tile completes its hand
+                if self.state.can_tsumo[cpu_index] {
+                    self.declare_tsumo(self.state.current_player);
+                    return Ok(&self.state);
                 }
-                
-                // Discard a random tile from hand
-                let mut rng = rand::thread_rng();
-                let discard_index = rng.gen_range(0..hand.len());
-                let discarded_tile = hand.remove(discard_index);
-                
-                // Sort hand after discarding
-                sort_hand(hand);
-                
-                self.state.discards[cpu_index].push(discarded_tile);
 
-                // Update last_discarder to current CPU player
-                self.state.last_discarder = Some(self.state.current_player);
+                // ...and always aborts a nine-terminal opening hand
+                if self.state.can_kyuushu[cpu_index] {
+                    self.abort_hand(WinType::NineTerminals);
+                    return Ok(&self.state);
+                }
 
-                // Update win flags (check if any player can ron the discarded tile)
-                // Note: Don't advance current_player yet, as handle_ron_phase will set it
-                self.update_win_flags();
-                
-                // Handle ron phase (check if any player can ron)
-                // This will set current_player to the first player who can ron, or advance to next if none
-                self.handle_ron_phase();
-            }
-            GamePhase::Ron => {
-                // Ron phase: CPU automatically rons, player can choose
-                let ron_player_index = match self.state.current_player {
-                    Player::Player => 0,
-                    Player::Cpu1 => 1,
-                    Player::Cpu2 => 2,
-                    Player::Cpu3 => 3,
-                };
-                
-                // Check if this player can actually ron
-                if !self.state.can_ron[ron_player_index] {
-                    // Player cannot ron (should not happen, but handle gracefully)
-                    self.state.phase = GamePhase::Draw;
+                // Kan first if it does not break the hand; the discard comes on the next step
+                if let Some(option) = self.cpu_kan_choice(self.state.current_player) {
+                    self.declare_kan(self.state.current_player, option);
                     return Ok(&self.state);
                 }
-                
-                // CPU automatically rons
-                match self.state.current_player {
-                    Player::Cpu1 | Player::Cpu2 | Player::Cpu3 => {
-                        // CPU rons: game ends
-                        self.state.phase = GamePhase::End;
+
+                let seat = self.state.current_player;
+                let drawn = self.state.drawn_tile[cpu_index];
+                let tile = if let Some(drawn) = drawn.filter(|_| self.state.riichi[cpu_index]) {
+                    // Riichi: the drawn tile always goes
+                    drawn
+                } else if let Some(&id) = self.riichi_discards(seat).first() {
+                    // CPU declares riichi as soon as a discard leaves it tenpai
+                    self.declare_riichi(seat);
+                    let hand = &self.state.hands[cpu_index];
+                    match hand.iter().chain(drawn.iter()).find(|t| t.id == id) {
+                        Some(&tile) => tile,
+                        None => return Err("CPU riichi tile not found".into()),
                     }
-                    Player::Player => {
-                        // Player can choose to ron or pass (handled by frontend)
-                        // Do nothing here, wait for player_ron or player_pass command
-                        // Return current state so frontend can show ron/pass buttons
+                } else {
+                    // Discard a random tile from hand (including drawn_tile if present)
+                    let tiles: Vec<Tile> = self.state.hands[cpu_index].iter().chain(drawn.iter()).copied().collect();
+                    if tiles.is_empty() {
+                        return Err("CPU hand is empty".into());
                     }
-                }
+                    tiles[self.rng.gen_range(0..tiles.len())]
+                };
+
+                self.discard(seat, tile)?;
+            }
+            GamePhase::Ron => {
+                // CPUs always ron; the player could not, or it would be the player's turn to decide
+                self.resolve_rons(false);
+            }
+            GamePhase::Call => {
+                return Err("Waiting for player's call".into());
             }
             GamePhase::End => {
                 return Err("Game has ended".into());
@@ -292,134 +606,816 @@ impl GameEngine {
         Ok(&self.state)
     }
 
-    /// Handle ron phase: check if any player can ron and set phase accordingly
-    /// If no one can ron, advance to the next player after the one who discarded and set phase to Draw
-    fn handle_ron_phase(&mut self) {
-        // If any player can ron, set phase to Ron for the first player who can ron
-        // Otherwise, set phase to Draw for the next player after the one who discarded
-        let mut ron_player_found = false;
-        for (index, can_ron) in self.state.can_ron.iter().enumerate() {
-            if *can_ron {
-                let ron_player = match index {
-                    0 => Player::Player,
-                    1 => Player::Cpu1,
-                    2 => Player::Cpu2,
-                    3 => Player::Cpu3,
-                    _ => continue,
+    /// Move `tile` from `seat`'s drawn tile or hand to the pond, then offer it to the other seats.
+    /// The drawn tile is used when it is that exact copy; otherwise it joins the hand first.
+    fn discard(&mut self, seat: Player, tile: Tile) -> Result<(), String> {
+        let index = seat.index();
+        let drawn = self.state.drawn_tile[index];
+        let hand = &mut self.state.hands[index];
+
+        let tsumogiri = drawn == Some(tile);
+        let tile = match drawn {
+            Some(drawn) if drawn == tile => drawn,
+            _ => {
+                let Some(pos) = hand.iter().position(|t| *t == tile) else {
+                    return Err("Tile not found in hand".into());
                 };
-                // Set current player to the one who can ron and phase to Ron
-                self.state.current_player = ron_player;
-                self.state.phase = GamePhase::Ron;
-                ron_player_found = true;
-                break;
+                let tile = hand.remove(pos);
+                hand.extend(drawn);
+                sort_hand(hand);
+                tile
             }
+        };
+        self.state.drawn_tile[index] = None;
+        self.state.discards[index].push(tile);
+        self.record(GameEvent::Discard { seat, tile, tsumogiri });
+
+        // Update last_discarder to the discarding seat
+        self.state.last_discarder = Some(seat);
+        self.rinshan_draw = false;
+        // Ippatsu ends with the riichi player's next discard
+        if self.pending_riichi != Some(seat) {
+            self.state.ippatsu[index] = false;
         }
-        
-        // If no one can ron, advance to the next player after the one who discarded
-        if !ron_player_found {
-            if let Some(discarder) = self.state.last_discarder {
-                self.state.current_player = discarder.next();
-            } else {
-                // Fallback: advance current player (should not happen in normal game flow)
-                self.state.current_player = self.state.current_player.next();
+        // Temporary furiten lasts until the seat's own discard
+        self.state.furiten[index].temporary = false;
+
+        // Update win flags (check if any player can ron the discarded tile)
+        // Note: Don't advance current_player yet, as handle_ron_phase will set it
+        self.update_win_flags();
+
+        // Handle ron phase (check if any player can ron)
+        // This will set current_player to the first player who can ron, or advance to next if none
+        self.handle_ron_phase();
+        Ok(())
+    }
+
+    /// Conditions for riichi other than the discard: closed hand, 1000 points and four tiles left to draw
+    fn check_riichi(&self, seat: Player) -> Result<(), String> {
+        let index = seat.index();
+        if self.state.riichi[index] {
+            return Err("Already in riichi".into());
+        }
+        if !self.state.melds[index].iter().all(Meld::is_concealed) {
+            return Err("Riichi requires a closed hand".into());
+        }
+        if self.state.scores[index] < RIICHI_STICK {
+            return Err("Not enough points for riichi".into());
+        }
+        if self.wall.len() < 4 {
+            return Err("Not enough tiles left for riichi".into());
+        }
+        Ok(())
+    }
+
+    /// Tile ids `seat` can discard to declare riichi (those that leave the hand tenpai)
+    fn riichi_discards(&self, seat: Player) -> Vec<u8> {
+        let index = seat.index();
+        let Some(drawn) = self.state.drawn_tile[index] else {
+            return Vec::new();
+        };
+        if self.check_riichi(seat).is_err() {
+            return Vec::new();
+        }
+
+        let mut tiles = self.state.hands[index].clone();
+        tiles.push(drawn);
+        sort_hand(&mut tiles);
+        let mut ids: Vec<u8> = tiles.iter().map(|t| t.id).collect();
+        ids.dedup();
+        ids.retain(|&id| {
+            let mut rest = tiles.clone();
+            if let Some(pos) = rest.iter().position(|t| t.id == id) {
+                rest.remove(pos);
             }
-            self.state.phase = GamePhase::Draw;
+            !tenpai_waits(&rest).is_empty()
+        });
+        ids
+    }
+
+    /// Mark `seat` as in riichi before its sideways discard
+    fn declare_riichi(&mut self, seat: Player) {
+        let index = seat.index();
+        let first_turn = self.is_first_turn(seat);
+        self.state.riichi[index] = true;
+        self.state.double_riichi[index] = first_turn;
+        self.state.ippatsu[index] = true;
+        self.state.riichi_discard[index] = Some(self.state.discards[index].len());
+        self.pending_riichi = Some(seat);
+        self.record(GameEvent::Riichi { seat, open: self.state.open_riichi[index] });
+    }
+
+    /// `seat` has not discarded yet and nobody has called or declared a kan
+    fn is_first_turn(&self, seat: Player) -> bool {
+        self.state.discards[seat.index()].is_empty() && self.state.melds.iter().all(Vec::is_empty)
+    }
+
+    /// The riichi discard went through without a ron: pay the stick
+    fn deposit_riichi_stick(&mut self) {
+        if let Some(seat) = self.pending_riichi.take() {
+            self.state.scores[seat.index()] -= RIICHI_STICK;
+            self.state.riichi_sticks += 1;
         }
     }
 
-    /// Update win flags (can_tsumo and can_ron) for all players
-    fn update_win_flags(&mut self) {
-        // Reset flags
-        self.state.can_tsumo = [false; 4];
-        self.state.can_ron = [false; 4];
+    /// End the game with a tsumo win for the given player.
+    /// The winning tile stays in drawn_tile so the frontend can show it separately.
+    fn declare_tsumo(&mut self, winner: Player) {
+        if let Some(winning_tile) = self.state.drawn_tile[winner.index()] {
+            self.record(GameEvent::Tsumo { winner, tile: winning_tile });
+            let mut result = GameResult::tsumo(winner, winning_tile);
+            let ctx = self.win_context(winner, winning_tile, true);
+            self.settle_win(&mut result, winner, &ctx, true);
+            self.state.result = Some(result);
+        }
+        self.state.phase = GamePhase::End;
+    }
 
-        // Check tsumo for each player (if in Discard phase after drawing, hand + drawn_tile = 14 tiles)
-        if self.state.phase == GamePhase::Discard {
-            for index in 0..4 {
-                // Combine hand and drawn_tile for win check
-                let mut full_hand = self.state.hands[index].clone();
-                if let Some(drawn) = self.state.drawn_tile[index] {
-                    full_hand.push(drawn);
-                }
-                
-                if full_hand.len() == 14 && can_win(&full_hand) {
-                    self.state.can_tsumo[index] = true;
-                }
+    /// End the game with ron wins on the last discarded tile.
+    /// Only the first winner in turn order collects honba and riichi sticks.
+    fn finish_ron(&mut self, winners: &[Player]) {
+        // A riichi discard that deals in is not a riichi: no stick is paid
+        self.pending_riichi = None;
+        if let Some((discarder, winning_tile)) = self.ron_target() {
+            let mut results = Vec::new();
+            for (order, &winner) in winners.iter().enumerate() {
+                self.record(GameEvent::Ron { winner, from: discarder, tile: winning_tile });
+                let mut result = GameResult::ron(winner, winning_tile, discarder);
+                let ctx = self.win_context(winner, winning_tile, false);
+                self.settle_win(&mut result, winner, &ctx, order == 0);
+                results.push(result);
             }
+            let mut results = results.into_iter();
+            self.state.result = results.next();
+            self.state.additional_results = results.collect();
         }
+        // A robbed kan falls back to the pon
+        if let Some((seat, tile)) = self.chankan.take() {
+            if let Some(meld) = self.state.melds[seat.index()]
+                .iter_mut()
+                .find(|m| m.kind == MeldKind::Shouminkan && m.tiles[0].id == tile.id)
+            {
+                meld.kind = MeldKind::Pon;
+                meld.tiles.pop();
+            }
+        }
+        self.state.phase = GamePhase::End;
+    }
 
-        // Check ron for each player (if last discarded tile can complete their hand)
-        // Use last_discarder to find the last discarded tile
-        if let Some(discarder) = self.state.last_discarder {
-            let discarder_index = match discarder {
-                Player::Player => 0,
-                Player::Cpu1 => 1,
-                Player::Cpu2 => 2,
-                Player::Cpu3 => 3,
-            };
-            
-            // Get the last discarded tile from the discarder's discards
-            if let Some(discarded_tile) = self.state.discards[discarder_index].last() {
-                // Check ron for all players except the one who discarded
-                for (index, hand) in self.state.hands.iter().enumerate() {
-                    if index == discarder_index {
-                        continue; // Don't check ron for the player who just discarded
-                    }
-                    if can_win_by_ron(hand, *discarded_tile) {
-                        self.state.can_ron[index] = true;
-                    }
+    /// Tenpai for noten payments: a wait whose four copies are all in the seat's
+    /// own hand and melds cannot be won on, so it does not count
+    fn is_tenpai_at_draw(&self, seat: Player) -> bool {
+        let index = seat.index();
+        let hand = &self.state.hands[index];
+        let own: Vec<Tile> = hand.iter().chain(self.state.melds[index].iter().flat_map(|m| &m.tiles)).copied().collect();
+        waits_with_remaining(hand, &count_tiles(&own)).iter().any(|wait| wait.remaining > 0)
+    }
+
+    /// Score the winner's hand and move points between seats
+    fn settle_win(&mut self, result: &mut GameResult, winner: Player, ctx: &WinContext, collects_bonus: bool) {
+        let melds = &self.state.melds[winner.index()];
+        let mut full_hand = self.state.hands[winner.index()].clone();
+        full_hand.push(ctx.win_tile);
+
+        let riichi = ctx.is_riichi || ctx.is_double_riichi;
+        let all_tiles: Vec<Tile> = full_hand.iter().chain(melds.iter().flat_map(|m| &m.tiles)).copied().collect();
+        let dora = self.dead_wall.count_dora(&all_tiles, riichi);
+        if riichi {
+            result.ura_dora_indicators = self.dead_wall.ura_dora_indicators().to_vec();
+        }
+
+        if let Some(score) = score_hand(&full_hand, melds, ctx, dora) {
+            result.point_changes = score.point_changes(winner, result.deal_in, self.state.dealer);
+            // Honba: 300 each, from the discarder or 100 from every other seat on tsumo
+            let honba = if collects_bonus { self.state.honba as i32 } else { 0 };
+            for seat in 0..4 {
+                if seat != winner.index() && result.deal_in.is_none_or(|d| d.index() == seat) {
+                    let paid = if result.deal_in.is_some() { 300 * honba } else { 100 * honba };
+                    result.point_changes[seat] -= paid;
+                    result.point_changes[winner.index()] += paid;
                 }
             }
+            // The winner collects the riichi sticks on the table
+            if collects_bonus {
+                result.point_changes[winner.index()] += self.state.riichi_sticks as i32 * RIICHI_STICK;
+                self.state.riichi_sticks = 0;
+            }
+            for (points, change) in self.state.scores.iter_mut().zip(result.point_changes) {
+                *points += change;
+            }
+            self.record(GameEvent::Score { point_changes: result.point_changes, scores: self.state.scores });
+            result.score = Some(score);
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_new_game_deals_tiles() {
-        let mut engine = GameEngine::new();
-        let state = engine.new_game();
+    /// End the game because the wall ran out: record who is tenpai and
+    /// settle the noten payments, or nagashi mangan instead when a seat qualifies
+    fn finish_exhaustive_draw(&mut self) {
+        let mut result = GameResult::exhaustive_draw();
+        result.tenpai = std::array::from_fn(|index| self.is_tenpai_at_draw(Player::from_index(index)));
+        result.dealer_tenpai = result.tenpai[self.state.dealer.index()];
+        result.nagashi_mangan = (0..4).map(Player::from_index).filter(|&seat| self.is_nagashi_mangan(seat)).collect();
 
-        // All players have 13 tiles in hand, player has 1 drawn tile
-        assert_eq!(state.hands[0].len(), 13);
-        assert_eq!(state.hands[1].len(), 13);
-        assert_eq!(state.hands[2].len(), 13);
-        assert_eq!(state.hands[3].len(), 13);
-        assert!(state.drawn_tile[0].is_some());
-        // Wall count should be 136 - 52 - 1 = 83
-        assert_eq!(state.wall_count, 83);
-        // Phase and current player set
-        assert_eq!(state.phase, GamePhase::Discard);
-        assert_eq!(state.current_player, Player::Player);
+        if result.nagashi_mangan.is_empty() {
+            result.point_changes = noten_payments(&result.tenpai);
+        } else {
+            for &seat in &result.nagashi_mangan {
+                let changes = nagashi_mangan_payments(seat, self.state.dealer);
+                for (total, change) in result.point_changes.iter_mut().zip(changes) {
+                    *total += change;
+                }
+            }
+        }
+        for (points, change) in self.state.scores.iter_mut().zip(result.point_changes) {
+            *points += change;
+        }
+        self.record(GameEvent::ExhaustiveDraw { tenpai: result.tenpai });
+        self.record(GameEvent::Score { point_changes: result.point_changes, scores: self.state.scores });
+        self.state.result = Some(result);
+        self.state.phase = GamePhase::End;
     }
 
-    #[test]
-    fn test_get_state_returns_clone() {
-        let mut engine = GameEngine::new();
-        engine.new_game();
-        let state = engine.get_state();
-        assert_eq!(state.hands.len(), 4);
+    /// Every discard of `seat` is a terminal or honor and none was called by another seat
+    fn is_nagashi_mangan(&self, seat: Player) -> bool {
+        let discards = &self.state.discards[seat.index()];
+        !discards.is_empty()
+            && discards.iter().all(|tile| tile.is_terminal_or_honor())
+            && self.state.melds.iter().flatten().all(|meld| meld.from != Some(seat))
+    }
+
+    /// End the hand with an abortive draw; no points move and riichi sticks stay on the table
+    fn abort_hand(&mut self, win_type: WinType) {
+        self.pending_riichi = None;
+        self.record(GameEvent::Abort { kind: win_type });
+        self.state.result = Some(GameResult::draw(win_type));
+        self.state.phase = GamePhase::End;
+    }
+
+    /// Abortive draw reached once a discard has gone through without a ron
+    fn abortive_draw_after_discard(&self) -> Option<WinType> {
+        let rules = self.state.rules.abortive_draws;
+        let discards = &self.state.discards;
+        let no_calls = self.state.melds.iter().all(Vec::is_empty);
+        let first_id = discards[0].first().map(|t| t.id);
+        if rules.four_winds
+            && no_calls
+            && discards.iter().all(|pond| pond.len() == 1 && Some(pond[0].id) == first_id)
+            && first_id.is_some_and(|id| (27..=30).contains(&id))
+        {
+            return Some(WinType::FourWinds);
+        }
+        if rules.four_riichi && self.state.riichi.iter().all(|&riichi| riichi) {
+            return Some(WinType::FourRiichi);
+        }
+        let kans: Vec<usize> = self.state.melds.iter().map(|melds| melds.iter().filter(|m| m.is_kan()).count()).collect();
+        // Four kans by one seat is a suukantsu wait, not an abort
+        if rules.four_kans && kans.iter().sum::<usize>() == 4 && !kans.contains(&4) {
+            return Some(WinType::FourKans);
+        }
+        None
+    }
+
+    /// Kyuushu kyuuhai: `seat`'s first draw with nobody having called, holding nine different terminals/honors
+    fn can_declare_kyuushu(&self, seat: Player) -> bool {
+        let index = seat.index();
+        let Some(drawn) = self.state.drawn_tile[index] else {
+            return false;
+        };
+        if !self.state.rules.abortive_draws.nine_terminals || !self.is_first_turn(seat) {
+            return false;
+        }
+        let mut ids: Vec<u8> = self.state.hands[index]
+            .iter()
+            .chain(std::iter::once(&drawn))
+            .filter(|t| t.is_terminal_or_honor())
+            .map(|t| t.id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.len() >= 9
+    }
+
+    /// Open the ron phase when any seat can ron the last discard, otherwise the call window.
+    /// The player decides first when able to ron; CPU claims are settled on the next CPU step.
+    fn handle_ron_phase(&mut self) {
+        let claimant = self.ron_order().into_iter().find(|seat| self.state.can_ron[seat.index()]);
+        match claimant {
+            Some(seat) => {
+                self.state.current_player = if self.state.can_ron[0] { Player::Player } else { seat };
+                self.state.phase = GamePhase::Ron;
+            }
+            None => self.open_call_window(),
+        }
+    }
+
+    /// Seats after the last discarder (or the seat adding to a kan) in turn order
+    fn ron_order(&self) -> Vec<Player> {
+        let Some((discarder, _)) = self.ron_target() else {
+            return Vec::new();
+        };
+        (1..4).map(|offset| Player::from_index(discarder.index() + offset)).collect()
+    }
+
+    /// Settle the ron claims on the last discard once the player has decided; CPUs always claim
+    fn resolve_rons(&mut self, player_rons: bool) {
+        let mut winners: Vec<Player> = self
+            .ron_order()
+            .into_iter()
+            .filter(|&seat| if seat == Player::Player { player_rons } else { self.state.can_ron[seat.index()] })
+            .collect();
+        if winners.is_empty() {
+            self.state.can_ron = [false; 4];
+            match self.chankan {
+                Some((seat, _)) => {
+                    self.mark_missed_wins();
+                    self.complete_kan(seat);
+                }
+                None => self.open_call_window(),
+            }
+            return;
+        }
+        if winners.len() == 3 && self.state.rules.abortive_draws.triple_ron {
+            self.abort_hand(WinType::TripleRon);
+            return;
+        }
+        if self.state.rules.ron_resolution == RonResolution::HeadBump {
+            winners.truncate(1);
+        }
+        self.finish_ron(&winners);
+    }
+
+    /// Offer pon/chi/kan on the last discard.
+    /// The human is asked first; otherwise CPU calls are resolved right away.
+    fn open_call_window(&mut self) {
+        self.deposit_riichi_stick();
+        self.mark_missed_wins();
+        if let Some(win_type) = self.abortive_draw_after_discard() {
+            self.abort_hand(win_type);
+            return;
+        }
+        self.state.call_options = Default::default();
+        if let Some(discarder) = self.state.last_discarder {
+            if let Some(&tile) = self.state.discards[discarder.index()].last() {
+                // No calls on the very last discard of the hand
+                if !self.wall.is_empty() {
+                    for seat in [Player::Player, Player::Cpu1, Player::Cpu2, Player::Cpu3] {
+                        // A riichi hand cannot call
+                        if seat != discarder && !self.state.riichi[seat.index()] {
+                            self.state.call_options[seat.index()] = self.call_options_for(seat, discarder, tile);
+                        }
+                    }
+                }
+            }
+        }
+
+        if !self.state.call_options[0].is_empty() {
+            self.state.current_player = Player::Player;
+            self.state.phase = GamePhase::Call;
+        } else {
+            self.resolve_calls(None);
+        }
+    }
+
+    /// Nobody ronned the last discard or added kan tile: seats it would have completed become furiten
+    fn mark_missed_wins(&mut self) {
+        let Some((discarder, tile)) = self.ron_target() else { return };
+        for index in (0..4).filter(|&i| i != discarder.index()) {
+            if tenpai_waits(&self.state.hands[index]).contains(&tile.id) {
+                self.state.furiten[index].temporary = true;
+                if self.state.riichi[index] {
+                    self.state.furiten[index].riichi = true;
+                }
+            }
+        }
+    }
+
+    /// Calls `seat` could make on `tile` discarded by `discarder`
+    fn call_options_for(&self, seat: Player, discarder: Player, tile: Tile) -> Vec<CallOption> {
+        let hand = &self.state.hands[seat.index()];
+        let matching: Vec<Tile> = hand.iter().filter(|t| t.id == tile.id).copied().collect();
+        let mut options = Vec::new();
+
+        if matching.len() >= 2 {
+            options.push(CallOption { kind: MeldKind::Pon, tiles: matching[..2].to_vec() });
+        }
+        if matching.len() >= 3 {
+            options.push(CallOption { kind: MeldKind::Daiminkan, tiles: matching[..3].to_vec() });
+        }
+
+        // Chi only from the player on the left, and only on suited tiles
+        if seat == discarder.next() && tile.id < 27 {
+            let position = tile.id % 9;
+            for start in position.saturating_sub(2)..=position.min(6) {
+                let base = tile.id - position + start;
+                let mut tiles = Vec::new();
+                for id in (base..base + 3).filter(|&id| id != tile.id) {
+                    if let Some(found) = hand.iter().find(|t| t.id == id) {
+                        tiles.push(*found);
+                    }
+                }
+                if tiles.len() == 2 {
+                    options.push(CallOption { kind: MeldKind::Chi, tiles });
+                }
+            }
+        }
+
+        options
+    }
+
+    /// CPU call policy: pon a pair of value honors, which always gives yakuhai
+    fn cpu_call_choice(&self, seat: Player) -> Option<CallOption> {
+        let seat_wind = seat.seat_wind(self.state.dealer).tile_id();
+        let round_wind = self.state.round_wind.tile_id();
+        self.state.call_options[seat.index()]
+            .iter()
+            .find(|option| {
+                let id = option.tiles[0].id;
+                option.kind == MeldKind::Pon && (id >= 31 || id == seat_wind || id == round_wind)
+            })
+            .cloned()
+    }
+
+    /// Apply the winning claim on the last discard (pon/kan beat chi), or move on to the next draw
+    fn resolve_calls(&mut self, player_call: Option<CallOption>) {
+        let mut best = player_call.map(|option| (Player::Player, option));
+        for seat in [Player::Cpu1, Player::Cpu2, Player::Cpu3] {
+            if let Some(option) = self.cpu_call_choice(seat) {
+                let beats_best = match &best {
+                    None => true,
+                    Some((_, current)) => current.kind == MeldKind::Chi && option.kind != MeldKind::Chi,
+                };
+                if beats_best {
+                    best = Some((seat, option));
+                }
+            }
+        }
+
+        match best {
+            Some((caller, option)) => self.apply_call(caller, option),
+            None => {
+                self.state.call_options = Default::default();
+                if let Some(discarder) = self.state.last_discarder {
+                    self.state.current_player = discarder.next();
+                } else {
+                    // Fallback: advance current player (should not happen in normal game flow)
+                    self.state.current_player = self.state.current_player.next();
+                }
+                self.state.phase = GamePhase::Draw;
+            }
+        }
+    }
+
+    /// Move the called tiles into a meld and hand the turn to the caller.
+    /// The called tile stays in the discarder's pond, marked by `Meld::from`.
+    fn apply_call(&mut self, caller: Player, option: CallOption) {
+        let Some(discarder) = self.state.last_discarder else { return };
+        let Some(&called_tile) = self.state.discards[discarder.index()].last() else { return };
+
+        let hand = &mut self.state.hands[caller.index()];
+        for tile in &option.tiles {
+            if let Some(pos) = hand.iter().position(|t| t == tile) {
+                hand.remove(pos);
+            }
+        }
+        let mut tiles = option.tiles;
+        tiles.push(called_tile);
+        sort_hand(&mut tiles);
+        self.record(GameEvent::Call {
+            seat: caller,
+            kind: option.kind,
+            tiles: tiles.clone(),
+            from: Some(discarder),
+        });
+        self.state.melds[caller.index()].push(Meld {
+            kind: option.kind,
+            tiles,
+            called_tile: Some(called_tile),
+            from: Some(discarder),
+        });
+
+        self.state.call_options = Default::default();
+        self.state.ippatsu = [false; 4];
+        self.state.current_player = caller;
+        self.state.phase = GamePhase::Discard;
+
+        // An open kan needs a replacement tile before the discard
+        if option.kind == MeldKind::Daiminkan {
+            self.draw_replacement(caller);
+        }
+        self.update_win_flags();
+    }
+
+    /// Closed and added kans `seat` may declare; only right after a draw, and while the dead wall allows
+    fn kan_options_for(&self, seat: Player) -> Vec<CallOption> {
+        let Some(drawn) = self.state.drawn_tile[seat.index()] else {
+            return Vec::new();
+        };
+        if self.wall.is_empty() || !self.dead_wall.can_kan() {
+            return Vec::new();
+        }
+
+        let mut tiles = self.state.hands[seat.index()].clone();
+        tiles.push(drawn);
+        sort_hand(&mut tiles);
+
+        let mut options = Vec::new();
+        for quad in tiles.chunk_by(|a, b| a.id == b.id).filter(|group| group.len() == 4) {
+            options.push(CallOption { kind: MeldKind::Ankan, tiles: quad.to_vec() });
+        }
+        for meld in self.state.melds[seat.index()].iter().filter(|m| m.kind == MeldKind::Pon) {
+            if let Some(tile) = tiles.iter().find(|t| t.id == meld.tiles[0].id) {
+                options.push(CallOption { kind: MeldKind::Shouminkan, tiles: vec![*tile] });
+            }
+        }
+
+        // In riichi only a closed kan of the drawn tile that keeps the same waits is allowed
+        if self.state.riichi[seat.index()] {
+            let hand = &self.state.hands[seat.index()];
+            let waits = tenpai_waits(hand);
+            options.retain(|option| {
+                let rest: Vec<Tile> = hand.iter().filter(|t| t.id != drawn.id).copied().collect();
+                option.kind == MeldKind::Ankan && option.tiles[0].id == drawn.id && tenpai_waits(&rest) == waits
+            });
+        }
+        options
+    }
+
+    /// CPU kan policy: only kan a tile with no neighbours in hand, so no sequence shape is lost
+    fn cpu_kan_choice(&self, seat: Player) -> Option<CallOption> {
+        let hand = &self.state.hands[seat.index()];
+        let drawn = self.state.drawn_tile[seat.index()];
+        self.state.kan_options[seat.index()]
+            .iter()
+            .find(|option| {
+                let id = option.tiles[0].id;
+                id >= 27
+                    || hand.iter().chain(drawn.iter()).all(|t| {
+                        t.id == id || t.id / 9 != id / 9 || t.id.abs_diff(id) > 2
+                    })
+            })
+            .cloned()
+    }
+
+    /// Declare a closed or added kan, then draw the replacement tile
+    fn declare_kan(&mut self, seat: Player, option: CallOption) {
+        let index = seat.index();
+        let hand = &mut self.state.hands[index];
+        if let Some(drawn) = self.state.drawn_tile[index].take() {
+            hand.push(drawn);
+            sort_hand(hand);
+        }
+        for tile in &option.tiles {
+            if let Some(pos) = hand.iter().position(|t| t == tile) {
+                hand.remove(pos);
+            }
+        }
+
+        self.record(GameEvent::Call {
+            seat,
+            kind: option.kind,
+            tiles: option.tiles.clone(),
+            from: None,
+        });
+        match option.kind {
+            MeldKind::Shouminkan => {
+                let added = option.tiles[0];
+                if let Some(meld) = self.state.melds[index]
+                    .iter_mut()
+                    .find(|m| m.kind == MeldKind::Pon && m.tiles[0].id == added.id)
+                {
+                    meld.kind = MeldKind::Shouminkan;
+                    meld.tiles.push(added);
+                }
+
+                // The other seats may rob the kan by ronning the added tile
+                self.chankan = Some((seat, added));
+                self.update_win_flags();
+                if self.state.can_ron.contains(&true) {
+                    self.handle_ron_phase();
+                    return;
+                }
+            }
+            _ => self.state.melds[index].push(Meld {
+                kind: MeldKind::Ankan,
+                tiles: option.tiles,
+                called_tile: None,
+                from: None,
+            }),
+        }
+
+        self.complete_kan(seat);
+    }
+
+    /// Finish a kan nobody robbed: ippatsu ends and `seat` draws its replacement tile
+    fn complete_kan(&mut self, seat: Player) {
+        self.chankan = None;
+        self.state.ippatsu = [false; 4];
+        self.state.current_player = seat;
+        self.state.phase = GamePhase::Discard;
+        self.draw_replacement(seat);
+        self.update_win_flags();
+    }
+
+    /// Seat and tile the other seats may ron: a tile being added to a pon, otherwise the last discard
+    fn ron_target(&self) -> Option<(Player, Tile)> {
+        if self.chankan.is_some() {
+            return self.chankan;
+        }
+        let discarder = self.state.last_discarder?;
+        self.state.discards[discarder.index()].last().map(|&tile| (discarder, tile))
+    }
+
+    /// Draw a kan replacement tile from the dead wall into `seat`'s drawn tile
+    fn draw_replacement(&mut self, seat: Player) {
+        if let Some(tile) = self.dead_wall.draw_replacement(&mut self.wall) {
+            self.state.drawn_tile[seat.index()] = Some(tile);
+            self.state.wall_count = self.wall.len();
+            self.record(GameEvent::Draw { seat, tile: Some(tile), rinshan: true });
+            self.state.dora_indicators = self.dead_wall.dora_indicators().to_vec();
+            if let Some(&indicator) = self.state.dora_indicators.last() {
+                self.record(GameEvent::DoraReveal { indicator });
+            }
+            self.rinshan_draw = true;
+        }
+    }
+
+    /// Human claims the last discard with a call of `kind`
+    /// `tiles` pick the copies to call with; they must match an offered option tile for tile by id.
+    fn player_call(&mut self, kind: MeldKind, tiles: Option<&[Tile]>) -> Result<&GameState, String> {
+        if self.state.current_player != Player::Player {
+            return Err("Not player's turn".into());
+        }
+        if self.state.phase != GamePhase::Call {
+            return Err("Not in call phase".into());
+        }
+
+        let mut chosen = tiles.map(<[Tile]>::to_vec);
+        if let Some(chosen) = &mut chosen {
+            sort_hand(chosen);
+        }
+        let mut option = self.state.call_options[0]
+            .iter()
+            .find(|option| {
+                option.kind == kind
+                    && chosen.as_ref().is_none_or(|chosen| {
+                        option.tiles.iter().map(|t| t.id).eq(chosen.iter().map(|t| t.id))
+                    })
+            })
+            .cloned()
+            .ok_or_else(|| format!("Player cannot {:?}", kind).to_lowercase())?;
+        if let Some(chosen) = chosen {
+            let mut hand = self.state.hands[0].clone();
+            for tile in &chosen {
+                let Some(pos) = hand.iter().position(|t| t == tile) else {
+                    return Err("Tile not found in hand".into());
+                };
+                hand.remove(pos);
+            }
+            option.tiles = chosen;
+        }
+
+        self.state.call_options[0].clear();
+        self.resolve_calls(Some(option));
+        Ok(&self.state)
+    }
+
+    /// Update win flags (can_tsumo and can_ron) and kan options for all players
+    fn update_win_flags(&mut self) {
+        // Reset flags
+        self.state.can_tsumo = [false; 4];
+        self.state.can_ron = [false; 4];
+        self.state.kan_options = Default::default();
+        self.state.can_riichi = [false; 4];
+        self.state.can_kyuushu = [false; 4];
+        self.update_discard_furiten();
+        self.state.waits = waits_with_remaining(&self.state.hands[0], &self.visible_counts(Player::Player));
+
+        // Kans and riichi can only be declared by the current player before discarding
+        if self.state.phase == GamePhase::Discard {
+            let current = self.state.current_player;
+            self.state.kan_options[current.index()] = self.kan_options_for(current);
+            self.state.can_riichi[current.index()] = !self.riichi_discards(current).is_empty();
+            self.state.can_kyuushu[current.index()] = self.can_declare_kyuushu(current);
+        }
+
+        // Check tsumo for each player (if in Discard phase after drawing, hand + drawn_tile = 14 tiles)
+        if self.state.phase == GamePhase::Discard {
+            for index in 0..4 {
+                if let Some(drawn) = self.state.drawn_tile[index] {
+                    let ctx = self.win_context(Player::from_index(index), drawn, true);
+                    if can_win_by_tsumo(&self.state.hands[index], &self.state.melds[index], drawn, &ctx) {
+                        self.state.can_tsumo[index] = true;
+                    }
+                }
+            }
+        }
+
+        // Check ron for each player (if the last discarded tile, or a tile added to a pon, completes their hand)
+        // The last discard can only be claimed until the next turn starts
+        let discard_is_live = |discarder: Player| {
+            self.state.phase != GamePhase::Discard || self.state.current_player == discarder
+        };
+        if let Some((discarder, discarded_tile)) = self.ron_target().filter(|(d, _)| discard_is_live(*d)) {
+            let discarder_index = discarder.index();
+            // Check ron for all players except the one who discarded
+            for index in 0..4 {
+                if index == discarder_index {
+                    continue; // Don't check ron for the player who just discarded
+                }
+                if self.state.furiten[index].is_furiten() {
+                    continue; // Furiten seats may only win by tsumo
+                }
+                let ctx = self.win_context(Player::from_index(index), discarded_tile, false);
+                if can_win_by_ron(&self.state.hands[index], &self.state.melds[index], discarded_tile, &ctx) {
+                    self.state.can_ron[index] = true;
+                }
+            }
+        }
+    }
+
+    /// Own-discard furiten: a wait of the concealed hand is already in the seat's pond
+    fn update_discard_furiten(&mut self) {
+        for index in 0..4 {
+            let waits = tenpai_waits(&self.state.hands[index]);
+            self.state.furiten[index].discard =
+                self.state.discards[index].iter().any(|tile| waits.contains(&tile.id));
+        }
+    }
+
+    /// Situational facts for a win by `seat` on `win_tile`
+    fn win_context(&self, seat: Player, win_tile: Tile, is_tsumo: bool) -> WinContext {
+        let seat_wind = seat.seat_wind(self.state.dealer);
+        let mut ctx = WinContext::new(win_tile, is_tsumo, seat_wind, self.state.round_wind);
+        ctx.is_riichi = self.state.riichi[seat.index()];
+        ctx.is_double_riichi = self.state.double_riichi[seat.index()];
+        ctx.is_open_riichi = self.state.open_riichi[seat.index()];
+        ctx.kuitan = self.state.rules.kuitan;
+        ctx.is_ippatsu = self.state.ippatsu[seat.index()];
+        ctx.is_rinshan = is_tsumo && self.rinshan_draw;
+        // Haitei does not apply to a replacement tile
+        ctx.is_last_tile = self.wall.is_empty() && !ctx.is_rinshan;
+        ctx.is_chankan = !is_tsumo && self.chankan.is_some();
+        // Tenhou / chiihou: the seat's first draw, uninterrupted by any call
+        ctx.is_first_draw = is_tsumo && self.is_first_turn(seat);
+        ctx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::win_checker::Wait;
+    use crate::game::yaku::Yaku;
+    use crate::models::game_state::STARTING_POINTS;
+    use crate::models::rules::AbortiveDraws;
+    use crate::models::tile::RedFives;
+
+    /// Fixed seed so tests that play from a dealt hand are repeatable
+    const TEST_SEED: u64 = 2024;
+
+    #[test]
+    fn test_new_game_deals_tiles() {
+        let mut engine = GameEngine::new();
+        let state = engine.new_game(Some(TEST_SEED));
+
+        // All players have 13 tiles in hand, player has 1 drawn tile
+        assert_eq!(state.hands[0].len(), 13);
+        assert_eq!(state.hands[1].len(), 13);
+        assert_eq!(state.hands[2].len(), 13);
+        assert_eq!(state.hands[3].len(), 13);
+        assert!(state.drawn_tile[0].is_some());
+        // Wall count should be 136 - 14 (dead wall) - 52 - 1 = 69
+        assert_eq!(state.wall_count, 69);
+        // Phase and current player set
+        assert_eq!(state.phase, GamePhase::Discard);
+        assert_eq!(state.current_player, Player::Player);
+    }
+
+    #[test]
+    fn test_get_state_returns_clone() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        let state = engine.get_state();
+        assert_eq!(state.hands.len(), 4);
     }
 
     #[test]
     fn test_player_draw() {
         let mut engine = GameEngine::new();
-        engine.new_game();
+        engine.new_game(Some(TEST_SEED));
         
         // Player discards to set up for next turn
-        let tile_id = engine.state.hands[0][0].id;
-        engine.player_discard(tile_id).unwrap(); // Player discards, turn goes to Cpu1
+        let tile = engine.state.hands[0][0];
+        engine.player_discard(tile).unwrap(); // Player discards, turn goes to Cpu1
         
-        // Simulate CPU turns to get back to player
-        engine.cpu_step().unwrap(); // Cpu1 draws
-        engine.cpu_step().unwrap(); // Cpu1 discards, turn goes to Cpu2
-        engine.cpu_step().unwrap(); // Cpu2 draws
-        engine.cpu_step().unwrap(); // Cpu2 discards, turn goes to Cpu3
-        engine.cpu_step().unwrap(); // Cpu3 draws
-        engine.cpu_step().unwrap(); // Cpu3 discards, turn goes to Player
+        // Simulate CPU turns to get back to player, passing on any call offered
+        while engine.state.current_player != Player::Player || engine.state.phase != GamePhase::Draw {
+            if engine.state.current_player == Player::Player {
+                engine.player_pass().unwrap();
+            } else {
+                engine.cpu_step().unwrap();
+            }
+        }
         
         // Now player should be in Draw phase
         assert_eq!(engine.state.current_player, Player::Player);
@@ -439,17 +1435,56 @@ mod tests {
         assert_eq!(state.current_player, Player::Player);
     }
 
+    #[test]
+    fn test_same_seed_replays_same_game() {
+        // Play the player's drawn tile every turn and let the CPUs run for a while
+        let play = |seed| {
+            let mut engine = GameEngine::new();
+            engine.new_game(Some(seed));
+            for _ in 0..60 {
+                let state = engine.get_state();
+                if state.phase == GamePhase::End {
+                    break;
+                }
+                if state.current_player == Player::Player {
+                    match state.phase {
+                        GamePhase::Draw => engine.player_draw().map(|_| ()),
+                        GamePhase::Discard => engine.player_discard(state.drawn_tile[0].unwrap()).map(|_| ()),
+                        _ => engine.player_pass().map(|_| ()),
+                    }
+                    .unwrap();
+                } else {
+                    engine.cpu_step().unwrap();
+                }
+            }
+            engine.get_state()
+        };
+
+        let first = play(TEST_SEED);
+        assert_eq!(first.seed, TEST_SEED);
+        assert_eq!(serde_json::to_string(&first).unwrap(), serde_json::to_string(&play(TEST_SEED)).unwrap());
+    }
+
+    #[test]
+    fn test_new_game_reports_its_seed() {
+        let mut engine = GameEngine::new();
+        let state = engine.new_game(None).clone();
+        let mut replay = GameEngine::new();
+        assert_eq!(replay.new_game(Some(state.seed)).hands, state.hands);
+        assert!(state.seed < 1 << 53);
+    }
+
     #[test]
     fn test_player_discard() {
         let mut engine = GameEngine::new();
-        engine.new_game();
+        engine.new_game(Some(TEST_SEED));
 
         // Test discarding the drawn tile
         let drawn_tile_id = engine.state.drawn_tile[0].map(|t| t.id);
         assert!(drawn_tile_id.is_some(), "Player should have a drawn tile after new_game");
         
         let before_hand_len = engine.state.hands[0].len();
-        let res = engine.player_discard(drawn_tile_id.unwrap());
+        let res = engine.player_discard(engine.state.drawn_tile[0].unwrap());
         assert!(res.is_ok());
 
         let state = res.unwrap();
@@ -464,7 +1499,7 @@ mod tests {
     #[test]
     fn test_player_discard_from_hand() {
         let mut engine = GameEngine::new();
-        engine.new_game();
+        engine.new_game(Some(TEST_SEED));
 
         // Test discarding from hand (not the drawn tile)
         let drawn_tile_id = engine.state.drawn_tile[0].map(|t| t.id).unwrap();
@@ -474,7 +1509,7 @@ mod tests {
         assert_ne!(hand_tile_id, drawn_tile_id, "Hand tile should be different from drawn tile");
         
         let before_hand_len = engine.state.hands[0].len();
-        let res = engine.player_discard(hand_tile_id);
+        let res = engine.player_discard(engine.state.hands[0][0]);
         assert!(res.is_ok());
 
         let state = res.unwrap();
@@ -490,11 +1525,11 @@ mod tests {
     #[test]
     fn test_cpu_step_draw() {
         let mut engine = GameEngine::new();
-        engine.new_game();
+        engine.new_game(Some(TEST_SEED));
         
         // Ensure player has a tile to discard
-        let tile_id = engine.state.hands[0][0].id;
-        engine.player_discard(tile_id).unwrap(); // Player discards, turn goes to Cpu1
+        let tile = engine.state.hands[0][0];
+        engine.player_discard(tile).unwrap(); // Player discards, turn goes to Cpu1
 
         let before_wall = engine.state.wall_count;
         let before_hand_len = engine.state.hands[1].len();
@@ -513,11 +1548,11 @@ mod tests {
     #[test]
     fn test_cpu_step_discard() {
         let mut engine = GameEngine::new();
-        engine.new_game();
+        engine.new_game(Some(TEST_SEED));
         
         // Ensure player has a tile to discard
-        let tile_id = engine.state.hands[0][0].id;
-        engine.player_discard(tile_id).unwrap(); // Player discards, turn goes to Cpu1
+        let tile = engine.state.hands[0][0];
+        engine.player_discard(tile).unwrap(); // Player discards, turn goes to Cpu1
         engine.cpu_step().unwrap(); // Cpu1 draws
 
         let before_hand_len = engine.state.hands[1].len();
@@ -534,6 +1569,1234 @@ mod tests {
         assert_eq!(state.current_player, Player::Cpu2);
         assert_eq!(state.phase, GamePhase::Draw);
     }
-}
 
+    /// Build tiles from ids for hand setup in tests
+    fn tiles(ids: &[u8]) -> Vec<Tile> {
+        ids.iter().map(|&id| Tile::new(id).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_player_tsumo_rejected_without_winning_hand() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.can_tsumo[0] = false;
+
+        assert!(engine.player_tsumo().is_err());
+        assert_eq!(engine.state.phase, GamePhase::Discard);
+        assert_eq!(engine.state.result, None);
+    }
+
+    #[test]
+    fn test_player_tsumo_ends_game() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.dead_wall = DeadWall::default(); // No dora indicators
+
+        // 123m 456m 123p 123s + East single wait, drawing the second East
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.discards[0] = tiles(&[33]); // Past the first draw, so no tenhou
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.update_win_flags();
+        assert!(engine.state.can_tsumo[0]);
+
+        let state = engine.player_tsumo().unwrap();
+        assert_eq!(state.phase, GamePhase::End);
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.winner, Some(Player::Player));
+        assert_eq!(result.win_type, WinType::Tsumo);
+        assert_eq!(result.winning_tile, Some(Tile::new(27).unwrap()));
+        // Dealer menzen tsumo + sanshoku, 3 han 30 fu: 2000 all
+        assert_eq!(result.point_changes, [6000, -2000, -2000, -2000]);
+        assert_eq!(state.scores, [31000, 23000, 23000, 23000]);
+    }
+
+    #[test]
+    fn test_tenhou_on_first_draw() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.update_win_flags();
+
+        let state = engine.player_tsumo().unwrap();
+        let score = state.result.clone().unwrap().score.unwrap();
+        assert_eq!(score.yaku, vec![Yaku::Tenhou]);
+        assert_eq!(state.result.clone().unwrap().point_changes, [48000, -16000, -16000, -16000]);
+    }
+
+    #[test]
+    fn test_honba_paid_on_tsumo() {
+        let mut engine = GameEngine::new();
+        engine.start_hand(HandSetup { honba: 2, ..HandSetup::default() });
+        engine.dead_wall = DeadWall::default();
+
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.discards[0] = tiles(&[33]); // Past the first draw, so no tenhou
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.update_win_flags();
+
+        let state = engine.player_tsumo().unwrap();
+        // 2000 all plus 100 per honba from each seat
+        assert_eq!(state.result.clone().unwrap().point_changes, [6600, -2200, -2200, -2200]);
+    }
+
+    #[test]
+    fn test_cpu_declares_tsumo() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        let tile = engine.state.hands[0][0];
+        engine.player_discard(tile).unwrap();
+        engine.state.can_ron = [false; 4];
+        engine.state.current_player = Player::Cpu1;
+        engine.state.phase = GamePhase::Draw;
+
+        // Rig the wall so Cpu1 draws its winning tile
+        engine.state.hands[1] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.wall.push(Tile::new(27).unwrap());
+        engine.cpu_step().unwrap(); // Cpu1 draws
+        assert!(engine.state.can_tsumo[1]);
+
+        let state = engine.cpu_step().unwrap(); // Cpu1 declares tsumo instead of discarding
+        assert_eq!(state.phase, GamePhase::End);
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.winner, Some(Player::Cpu1));
+        assert_eq!(result.win_type, WinType::Tsumo);
+        assert!(state.discards[1].is_empty());
+    }
+
+    #[test]
+    fn test_player_ron_records_result() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.dead_wall = DeadWall::default(); // No dora indicators
+        engine.player_discard(engine.state.drawn_tile[0].unwrap()).unwrap();
+        engine.state.can_ron = [false; 4];
+        engine.state.current_player = Player::Cpu1;
+        engine.state.phase = GamePhase::Draw;
+
+        // Cpu1 draws 6s and discards it into the player's tanyao single wait
+        engine.state.hands[0] = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23]);
+        engine.wall.push(Tile::new(23).unwrap());
+        engine.cpu_step().unwrap(); // Cpu1 draws
+        engine.state.hands[1] = Vec::new();
+        engine.update_win_flags();
+        engine.cpu_step().unwrap(); // Cpu1 discards the drawn 6s (its only tile)
+        assert_eq!(engine.state.phase, GamePhase::Ron);
+        assert_eq!(engine.state.current_player, Player::Player);
+
+        let state = engine.player_ron().unwrap();
+        assert_eq!(state.phase, GamePhase::End);
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.winner, Some(Player::Player));
+        assert_eq!(result.win_type, WinType::Ron);
+        assert_eq!(result.winning_tile, Some(Tile::new(23).unwrap()));
+        assert_eq!(result.deal_in, Some(Player::Cpu1));
+        // Dealer tanyao tanki, 1 han 40 fu: 2000
+        let score = result.score.unwrap();
+        assert_eq!((score.han, score.fu), (1, 40));
+        assert_eq!(result.point_changes, [2000, -2000, 0, 0]);
+        assert_eq!(state.scores[1], 23000);
+    }
+
+    #[test]
+    fn test_wall_exhaustion_records_draw() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.player_discard(engine.state.drawn_tile[0].unwrap()).unwrap();
+        engine.state.can_ron = [false; 4];
+        engine.state.current_player = Player::Cpu1;
+        engine.state.phase = GamePhase::Draw;
+        engine.wall.clear();
+
+        // Nobody tenpai and no nagashi: no points move
+        engine.state.hands = std::array::from_fn(|_| tiles(&[0, 2, 4, 9, 11, 13, 18, 20, 22, 27, 28, 29, 31]));
+        engine.state.discards[0] = tiles(&[3]);
+
+        let state = engine.cpu_step().unwrap();
+        assert_eq!(state.phase, GamePhase::End);
+        assert_eq!(state.result, Some(GameResult::exhaustive_draw()));
+        assert_eq!(state.scores, [STARTING_POINTS; 4]);
+    }
+
+    #[test]
+    fn test_exhaustive_draw_noten_payments() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.phase = GamePhase::Draw;
+        engine.wall.clear();
+        engine.state.discards = std::array::from_fn(|_| tiles(&[3]));
 
+        // Player and Cpu2 tenpai, the dealer (Player) keeps the deal
+        let noten = tiles(&[0, 2, 4, 9, 11, 13, 18, 20, 22, 27, 28, 29, 31]);
+        let tenpai = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.hands = [tenpai.clone(), noten.clone(), tenpai, noten];
+
+        let state = engine.player_draw().unwrap();
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.win_type, WinType::ExhaustiveDraw);
+        assert_eq!(result.tenpai, [true, false, true, false]);
+        assert!(result.dealer_tenpai);
+        assert_eq!(result.point_changes, [1500, -1500, 1500, -1500]);
+        assert_eq!(state.scores, [26500, 23500, 26500, 23500]);
+    }
+
+    #[test]
+    fn test_wait_used_up_by_own_kan_is_noten() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.phase = GamePhase::Draw;
+        engine.wall.clear();
+        engine.state.discards = std::array::from_fn(|_| tiles(&[3]));
+        let noten = tiles(&[0, 2, 4, 9, 11, 13, 18, 20, 22, 27, 28, 29, 31]);
+        // Cpu1 waits on 5m with 4m6m, but holds every 5m in its own kan
+        engine.state.hands = [noten.clone(), tiles(&[0, 1, 2, 15, 16, 17, 18, 18, 3, 5]), noten.clone(), noten];
+        engine.state.melds[1].push(Meld {
+            kind: MeldKind::Daiminkan,
+            tiles: tiles(&[4, 4, 4, 4]),
+            called_tile: Some(Tile::new(4).unwrap()),
+            from: Some(Player::Player),
+        });
+
+        let state = engine.player_draw().unwrap();
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.tenpai, [false; 4]);
+        assert_eq!(result.point_changes, [0; 4]);
+    }
+
+    #[test]
+    fn test_nagashi_mangan() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.phase = GamePhase::Draw;
+        engine.wall.clear();
+        engine.state.hands = std::array::from_fn(|_| tiles(&[0, 2, 4, 9, 11, 13, 18, 20, 22, 27, 28, 29, 31]));
+        engine.state.discards = [tiles(&[3]), tiles(&[0, 27, 33]), tiles(&[8, 9]), tiles(&[5])];
+        // Cpu2's 1p was called, so only Cpu1 qualifies
+        engine.state.melds[3].push(Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[9, 9, 9]),
+            called_tile: Some(Tile::new(9).unwrap()),
+            from: Some(Player::Cpu2),
+        });
+
+        let state = engine.player_draw().unwrap();
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.nagashi_mangan, vec![Player::Cpu1]);
+        assert_eq!(result.point_changes, [-4000, 8000, -2000, -2000]);
+    }
+
+    #[test]
+    fn test_yakuless_hand_cannot_ron() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.player_discard(engine.state.drawn_tile[0].unwrap()).unwrap();
+
+        // Complete shape on a 9m single wait but no yaku for the Cpu2 seat
+        engine.state.hands[2] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 8]);
+        engine.state.discards[0].push(Tile::new(8).unwrap());
+        engine.update_win_flags();
+        assert!(!engine.state.can_ron[2]);
+    }
+
+    /// Have `seat` discard `tile_id` as its only tile, opening the claim window
+    fn cpu_discards(engine: &mut GameEngine, seat: Player, tile_id: u8) {
+        engine.state.current_player = seat;
+        engine.state.phase = GamePhase::Discard;
+        engine.state.drawn_tile = [None; 4];
+        engine.state.hands[seat.index()] = tiles(&[tile_id]);
+        engine.cpu_step().unwrap();
+    }
+
+    #[test]
+    fn test_player_pon() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands = [
+            tiles(&[4, 4, 9, 10, 11, 12, 13, 14, 18, 19, 20, 31, 32]),
+            tiles(&[0, 8, 17]),
+            Vec::new(),
+            tiles(&[0, 8, 17]),
+        ];
+
+        cpu_discards(&mut engine, Player::Cpu2, 4);
+        assert_eq!(engine.state.phase, GamePhase::Call);
+        assert_eq!(engine.state.current_player, Player::Player);
+        assert_eq!(engine.state.call_options[0].len(), 1);
+        assert!(engine.player_chi(&tiles(&[3, 5])).is_err());
+
+        let state = engine.player_pon().unwrap();
+        assert_eq!(state.phase, GamePhase::Discard);
+        assert_eq!(state.current_player, Player::Player);
+        assert_eq!(state.hands[0].len(), 11);
+        let meld = &state.melds[0][0];
+        assert_eq!(meld.kind, MeldKind::Pon);
+        assert_eq!(meld.tiles, tiles(&[4, 4, 4]));
+        assert_eq!(meld.from, Some(Player::Cpu2));
+        assert!(state.call_options.iter().all(Vec::is_empty));
+
+        // Discard from hand after the call; play moves on from the caller
+        let state = engine.player_discard(Tile::new(31).unwrap()).unwrap();
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert_eq!(state.phase, GamePhase::Draw);
+    }
+
+    #[test]
+    fn test_player_chi_only_from_left() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[0, 1, 3, 9, 10, 11, 12, 13, 14, 18, 19, 20, 31]);
+        engine.state.hands[1] = tiles(&[8, 17]);
+        engine.state.hands[2] = tiles(&[8, 17]);
+
+        // Cpu2 is across: no chi
+        cpu_discards(&mut engine, Player::Cpu2, 2);
+        assert_eq!(engine.state.phase, GamePhase::Draw);
+        assert_eq!(engine.state.current_player, Player::Cpu3);
+
+        // Cpu3 is on the left: 1m2m and 2m4m both take the 3m
+        cpu_discards(&mut engine, Player::Cpu3, 2);
+        assert_eq!(engine.state.phase, GamePhase::Call);
+        assert_eq!(engine.state.call_options[0].len(), 2);
+        assert!(engine.player_pon().is_err());
+
+        let state = engine.player_chi(&tiles(&[3, 1])).unwrap();
+        assert_eq!(state.melds[0][0].kind, MeldKind::Chi);
+        assert_eq!(state.melds[0][0].tiles, tiles(&[1, 2, 3]));
+        assert_eq!(state.hands[0].len(), 11);
+        assert_eq!(state.phase, GamePhase::Discard);
+    }
+
+    #[test]
+    fn test_player_pass_on_call() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[4, 4, 9, 10, 11, 12, 13, 14, 18, 19, 20, 31, 32]);
+        engine.state.hands[1] = tiles(&[8, 17]);
+        engine.state.hands[3] = tiles(&[8, 17]);
+
+        cpu_discards(&mut engine, Player::Cpu2, 4);
+        let state = engine.player_pass().unwrap();
+        assert_eq!(state.current_player, Player::Cpu3);
+        assert_eq!(state.phase, GamePhase::Draw);
+        assert!(state.melds[0].is_empty());
+    }
+
+    #[test]
+    fn test_cpu_pons_value_pair() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[1] = tiles(&[33, 33, 0, 1, 2]);
+        engine.state.hands[2] = tiles(&[8, 17]);
+
+        // Player discards Red: Cpu1 pons its dragon pair
+        engine.state.hands[0] = tiles(&[33, 8, 17]);
+        let state = engine.player_discard(Tile::new(33).unwrap()).unwrap();
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert_eq!(state.phase, GamePhase::Discard);
+        assert_eq!(state.melds[1][0].tiles, tiles(&[33, 33, 33]));
+        assert_eq!(state.hands[1], tiles(&[0, 1, 2]));
+    }
+
+    #[test]
+    fn test_player_daiminkan_draws_replacement() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[4, 4, 4, 9, 10, 11, 12, 13, 14, 18, 19, 20, 31]);
+        engine.state.hands[1] = tiles(&[8, 17]);
+        engine.state.hands[3] = tiles(&[8, 17]);
+
+        cpu_discards(&mut engine, Player::Cpu2, 4);
+        assert_eq!(engine.state.call_options[0].len(), 2);
+        let wall_count = engine.state.wall_count;
+
+        let state = engine.player_daiminkan().unwrap();
+        assert_eq!(state.melds[0][0].kind, MeldKind::Daiminkan);
+        assert_eq!(state.melds[0][0].tiles.len(), 4);
+        assert_eq!(state.hands[0].len(), 10);
+        assert!(state.drawn_tile[0].is_some());
+        assert_eq!(state.wall_count, wall_count - 1);
+        assert_eq!(state.phase, GamePhase::Discard);
+    }
+
+    #[test]
+    fn test_player_ankan() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[4, 4, 4, 9, 10, 11, 12, 13, 14, 18, 19, 20, 31]);
+        engine.state.drawn_tile[0] = Some(Tile::new(4).unwrap());
+        engine.update_win_flags();
+        assert_eq!(engine.state.kan_options[0][0].kind, MeldKind::Ankan);
+        assert!(engine.player_kan(Tile::new(31).unwrap()).is_err());
+        let wall_count = engine.state.wall_count;
+
+        let state = engine.player_kan(Tile::new(4).unwrap()).unwrap();
+        let meld = &state.melds[0][0];
+        assert_eq!(meld.kind, MeldKind::Ankan);
+        assert_eq!(meld.tiles, tiles(&[4, 4, 4, 4]));
+        assert!(meld.is_concealed());
+        assert_eq!(state.hands[0].len(), 10);
+        assert!(state.drawn_tile[0].is_some());
+        assert_eq!(state.wall_count, wall_count - 1);
+        assert_eq!(state.phase, GamePhase::Discard);
+        assert_eq!(engine.dead_wall.dora_indicators().len(), 2);
+    }
+
+    #[test]
+    fn test_player_shouminkan() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[9, 10, 11, 12, 13, 14, 18, 19, 20, 31]);
+        engine.state.melds[0].push(Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[4, 4, 4]),
+            called_tile: Some(Tile::new(4).unwrap()),
+            from: Some(Player::Cpu2),
+        });
+        engine.state.drawn_tile[0] = Some(Tile::new(4).unwrap());
+        engine.update_win_flags();
+
+        let state = engine.player_kan(Tile::new(4).unwrap()).unwrap();
+        assert_eq!(state.melds[0].len(), 1);
+        assert_eq!(state.melds[0][0].kind, MeldKind::Shouminkan);
+        assert_eq!(state.melds[0][0].tiles.len(), 4);
+        assert_eq!(state.hands[0].len(), 10);
+        assert!(state.drawn_tile[0].is_some());
+    }
+
+    #[test]
+    fn test_shouminkan_adds_the_exact_copy() {
+        let red = Tile::red_five(4).unwrap();
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[9, 10, 11, 12, 13, 14, 18, 19, 20, 31]);
+        engine.state.melds[0].push(Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[4, 4, 4]),
+            called_tile: Some(Tile::new(4).unwrap()),
+            from: Some(Player::Cpu2),
+        });
+        engine.state.drawn_tile[0] = Some(red);
+        engine.update_win_flags();
+
+        // Only the red copy is held, so a plain 5m cannot be added
+        assert_eq!(engine.player_kan(Tile::new(4).unwrap()).err().unwrap(), "Player cannot kan");
+        let state = engine.player_kan(red).unwrap();
+        assert_eq!(state.melds[0][0].tiles.last(), Some(&red));
+    }
+
+    #[test]
+    fn test_rinshan_tsumo() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        // Dead wall of 9s so the replacement tile is known
+        engine.wall = tiles(&[26; 24]);
+        engine.dead_wall = DeadWall::split_from(&mut engine.wall);
+
+        // Ankan of North, then the replacement 9s completes the 9s tanki
+        engine.state.hands[0] = tiles(&[30, 30, 30, 1, 2, 3, 11, 12, 13, 22, 23, 24, 26]);
+        engine.state.drawn_tile[0] = Some(Tile::new(30).unwrap());
+        engine.update_win_flags();
+
+        let state = engine.player_kan(Tile::new(30).unwrap()).unwrap();
+        assert_eq!(state.drawn_tile[0], Some(Tile::new(26).unwrap()));
+        assert!(state.can_tsumo[0]);
+        let state = engine.player_tsumo().unwrap();
+        let score = state.result.clone().unwrap().score.unwrap();
+        assert!(score.yaku.contains(&Yaku::Rinshan));
+    }
+
+    /// Pon of 2s called from Cpu2
+    fn pon_of_2s() -> Vec<Meld> {
+        vec![Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[19, 19, 19]),
+            called_tile: Some(Tile::new(19).unwrap()),
+            from: Some(Player::Cpu2),
+        }]
+    }
+
+    #[test]
+    fn test_added_kan_can_be_robbed() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.melds[0] = pon_of_2s();
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 30]);
+        engine.state.drawn_tile[0] = Some(Tile::new(19).unwrap());
+        // Cpu1 waits on 2s with no yaku of its own
+        engine.state.hands[1] = tiles(&[0, 1, 2, 3, 4, 5, 15, 16, 17, 18, 20, 27, 27]);
+        engine.update_win_flags();
+
+        let state = engine.player_kan(Tile::new(19).unwrap()).unwrap();
+        assert_eq!(state.phase, GamePhase::Ron);
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert!(state.can_ron[1]);
+        // No replacement tile while the kan can still be robbed
+        assert_eq!(state.drawn_tile[0], None);
+
+        let state = engine.cpu_step().unwrap();
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.winner, Some(Player::Cpu1));
+        assert_eq!(result.deal_in, Some(Player::Player));
+        assert_eq!(result.winning_tile, Some(Tile::new(19).unwrap()));
+        assert!(result.score.unwrap().yaku.contains(&Yaku::Chankan));
+        assert_eq!(state.melds[0][0].kind, MeldKind::Pon);
+        assert_eq!(state.melds[0][0].tiles.len(), 3);
+    }
+
+    #[test]
+    fn test_passing_chankan_completes_the_kan() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 15, 16, 17, 18, 20, 27, 27]);
+        engine.state.drawn_tile[0] = None;
+        engine.state.melds[1] = pon_of_2s();
+        engine.state.hands[1] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 30]);
+        engine.state.drawn_tile[1] = Some(Tile::new(19).unwrap());
+        engine.state.current_player = Player::Cpu1;
+
+        engine.declare_kan(Player::Cpu1, CallOption { kind: MeldKind::Shouminkan, tiles: tiles(&[19]) });
+        assert_eq!(engine.state.phase, GamePhase::Ron);
+        assert_eq!(engine.state.current_player, Player::Player);
+        assert!(engine.state.can_ron[0]);
+
+        let state = engine.player_pass().unwrap();
+        assert_eq!(state.phase, GamePhase::Discard);
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert!(state.drawn_tile[1].is_some());
+        assert_eq!(state.melds[1][0].kind, MeldKind::Shouminkan);
+        assert!(!state.can_ron[0]);
+        assert!(state.furiten[0].temporary);
+    }
+
+    #[test]
+    fn test_cpu_kans_isolated_quad() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[1] = tiles(&[31, 31, 31, 0, 1, 2, 9, 10, 11, 18, 19, 20, 26]);
+        engine.state.drawn_tile[1] = Some(Tile::new(31).unwrap());
+        engine.state.current_player = Player::Cpu1;
+        engine.update_win_flags();
+
+        let state = engine.cpu_step().unwrap();
+        assert_eq!(state.melds[1][0].kind, MeldKind::Ankan);
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert_eq!(state.phase, GamePhase::Discard);
+
+        // Four 5m next to 4m and 6m stay in the hand
+        engine.state.hands[2] = tiles(&[4, 4, 4, 3, 5, 9, 10, 11, 18, 19, 20, 26, 26]);
+        engine.state.drawn_tile[2] = Some(Tile::new(4).unwrap());
+        engine.state.current_player = Player::Cpu2;
+        engine.update_win_flags();
+        assert!(!engine.state.kan_options[2].is_empty());
+        assert!(engine.cpu_kan_choice(Player::Cpu2).is_none());
+    }
+
+    #[test]
+    fn test_dora_counted_in_score() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        assert_eq!(engine.state.dora_indicators.len(), 1);
+
+        // Dead wall whose first indicator is North, making East the dora
+        engine.wall = tiles(&[0, 0, 0, 0, 30, 30, 30, 30, 30, 8, 8, 8, 8, 8, 5]);
+        engine.dead_wall = DeadWall::split_from(&mut engine.wall);
+
+        // Same hand as the tsumo test with its East pair as 2 dora
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.discards[0] = tiles(&[33]); // Past the first draw, so no tenhou
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.update_win_flags();
+
+        let state = engine.player_tsumo().unwrap();
+        let score = state.result.clone().unwrap().score.unwrap();
+        assert_eq!(score.dora.dora, 2);
+        assert_eq!(score.dora.ura_dora, 0);
+        // Tsumo + sanshoku + 2 dora: dealer mangan, 4000 all
+        assert_eq!(score.han, 5);
+        assert_eq!(state.result.clone().unwrap().point_changes, [12000, -4000, -4000, -4000]);
+    }
+
+    /// Dealer tenpai on a 9m or East tanki with the East drawn; CPUs hold tiles they cannot call with
+    fn riichi_setup() -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.dead_wall = DeadWall::default(); // No dora indicators
+        engine.state.hands = [
+            tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 8]),
+            tiles(&[17, 33]),
+            tiles(&[17, 33]),
+            tiles(&[17, 33]),
+        ];
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.update_win_flags();
+        engine
+    }
+
+    #[test]
+    fn test_player_riichi_deposits_stick_and_locks_hand() {
+        let mut engine = riichi_setup();
+        assert!(engine.state.can_riichi[0]);
+        assert!(engine.player_riichi(Tile::new(0).unwrap()).is_err());
+
+        let state = engine.player_riichi(Tile::new(27).unwrap()).unwrap();
+        assert!(state.riichi[0]);
+        assert!(state.double_riichi[0]);
+        assert!(state.ippatsu[0]);
+        assert_eq!(state.riichi_discard[0], Some(0));
+        assert_eq!(state.scores[0], 24000);
+        assert_eq!(state.riichi_sticks, 1);
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert!(engine.player_riichi(Tile::new(8).unwrap()).is_err());
+
+        // Back to the player: only the drawn tile may go
+        engine.state.current_player = Player::Player;
+        engine.state.phase = GamePhase::Draw;
+        engine.wall.push(Tile::new(31).unwrap());
+        engine.player_draw().unwrap();
+        assert!(!engine.state.can_riichi[0]);
+        assert!(engine.player_discard(Tile::new(0).unwrap()).is_err());
+        let state = engine.player_discard(Tile::new(31).unwrap()).unwrap();
+        assert!(!state.ippatsu[0]);
+        assert_eq!(state.hands[0].len(), 13);
+    }
+
+    #[test]
+    fn test_riichi_ron_collects_stick_with_ippatsu() {
+        let mut engine = riichi_setup();
+        // Not the first turn, so no double riichi
+        engine.state.discards[0].push(Tile::new(29).unwrap());
+        engine.player_riichi(Tile::new(27).unwrap()).unwrap();
+        assert!(!engine.state.double_riichi[0]);
+
+        cpu_discards(&mut engine, Player::Cpu1, 8);
+        let state = engine.player_ron().unwrap();
+        let result = state.result.clone().unwrap();
+        let score = result.score.unwrap();
+        assert!(score.yaku.contains(&Yaku::Riichi));
+        assert!(score.yaku.contains(&Yaku::Ippatsu));
+        // Dealer 2 han 40 fu ron is 3900, plus the riichi stick
+        assert_eq!(result.point_changes, [4900, -3900, 0, 0]);
+        assert_eq!(state.scores[0], 28900);
+        assert_eq!(state.riichi_sticks, 0);
+    }
+
+    #[test]
+    fn test_ronned_riichi_discard_pays_no_stick() {
+        let mut engine = riichi_setup();
+        // Cpu1 waits on East with a Haku triplet
+        engine.state.hands[1] = tiles(&[31, 31, 31, 1, 2, 3, 10, 11, 12, 20, 21, 22, 27]);
+
+        engine.player_riichi(Tile::new(27).unwrap()).unwrap();
+        assert_eq!(engine.state.phase, GamePhase::Ron);
+        assert_eq!(engine.state.current_player, Player::Cpu1);
+        let state = engine.cpu_step().unwrap();
+        let result = state.result.clone().unwrap();
+        assert_eq!(result.deal_in, Some(Player::Player));
+        assert_eq!(state.riichi_sticks, 0);
+        assert_eq!(state.scores[0], 25000 + result.point_changes[0]);
+    }
+
+    #[test]
+    fn test_cpu_declares_riichi_when_tenpai() {
+        let mut engine = riichi_setup();
+        engine.state.hands.swap(0, 1);
+        engine.state.drawn_tile = [None, Some(Tile::new(27).unwrap()), None, None];
+        engine.state.current_player = Player::Cpu1;
+        engine.update_win_flags();
+        assert!(engine.state.can_riichi[1]);
+
+        let state = engine.cpu_step().unwrap();
+        assert!(state.riichi[1]);
+        assert_eq!(state.riichi_discard[1], Some(0));
+        assert_eq!(state.scores[1], 24000);
+    }
+
+    #[test]
+    fn test_discard_furiten_blocks_ron() {
+        let mut engine = riichi_setup();
+        engine.state.hands[0] = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23]);
+        engine.state.discards[0] = tiles(&[23]);
+        engine.update_win_flags();
+        assert!(engine.state.furiten[0].discard);
+
+        // The 6s tanki would be tanyao, but the player already threw a 6s
+        cpu_discards(&mut engine, Player::Cpu1, 23);
+        assert!(!engine.state.can_ron[0]);
+        assert_eq!(engine.state.phase, GamePhase::Draw);
+        assert_eq!(engine.state.current_player, Player::Cpu2);
+    }
+
+    #[test]
+    fn test_passing_ron_is_temporary_furiten() {
+        let mut engine = riichi_setup();
+        engine.state.hands[0] = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23]);
+        engine.state.drawn_tile[0] = None;
+
+        cpu_discards(&mut engine, Player::Cpu1, 23);
+        assert_eq!(engine.state.phase, GamePhase::Ron);
+        engine.player_pass().unwrap();
+        assert!(engine.state.furiten[0].temporary);
+        assert!(!engine.state.furiten[0].riichi);
+
+        // Still furiten on the next discard of the same go-around
+        cpu_discards(&mut engine, Player::Cpu2, 23);
+        assert!(!engine.state.can_ron[0]);
+
+        // The player's own discard ends it
+        engine.state.current_player = Player::Player;
+        engine.state.phase = GamePhase::Discard;
+        engine.state.drawn_tile[0] = Some(Tile::new(33).unwrap());
+        engine.player_discard(Tile::new(33).unwrap()).unwrap();
+        assert!(!engine.state.furiten[0].is_furiten());
+    }
+
+    #[test]
+    fn test_riichi_furiten_is_permanent() {
+        let mut engine = riichi_setup();
+        engine.player_riichi(Tile::new(27).unwrap()).unwrap();
+
+        // 9m goes by while in riichi
+        cpu_discards(&mut engine, Player::Cpu1, 8);
+        engine.player_pass().unwrap();
+        assert!(engine.state.furiten[0].riichi);
+
+        engine.state.current_player = Player::Player;
+        engine.state.phase = GamePhase::Discard;
+        engine.state.drawn_tile[0] = Some(Tile::new(33).unwrap());
+        engine.player_discard(Tile::new(33).unwrap()).unwrap();
+        assert!(!engine.state.furiten[0].temporary);
+        assert!(engine.state.furiten[0].is_furiten());
+
+        cpu_discards(&mut engine, Player::Cpu1, 8);
+        assert!(!engine.state.can_ron[0]);
+    }
+
+    #[test]
+    fn test_open_riichi_needs_rule() {
+        let mut engine = riichi_setup();
+        assert!(engine.player_open_riichi(Tile::new(27).unwrap()).is_err());
+
+        engine.state.rules.open_riichi = true;
+        let state = engine.player_open_riichi(Tile::new(27).unwrap()).unwrap();
+        assert!(state.riichi[0]);
+        assert!(state.open_riichi[0]);
+    }
+
+    #[test]
+    fn test_discard_picks_the_exact_copy() {
+        let red = Tile::red_five(4).unwrap();
+        let plain = Tile::new(4).unwrap();
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = vec![plain, red, Tile::new(31).unwrap()];
+        engine.state.drawn_tile[0] = Some(plain);
+        let since = engine.events_since(0).len();
+
+        // The red copy leaves the hand even though a plain 5m was drawn
+        let state = engine.player_discard(red).unwrap();
+        assert_eq!(state.discards[0].last(), Some(&red));
+        assert_eq!(state.hands[0], vec![plain, plain, Tile::new(31).unwrap()]);
+        assert_eq!(engine.events_since(since)[0], GameEvent::Discard { seat: Player::Player, tile: red, tsumogiri: false });
+
+        // Discarding a plain copy from the hand keeps a drawn red one
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = vec![plain, Tile::new(31).unwrap()];
+        engine.state.drawn_tile[0] = Some(red);
+        let state = engine.player_discard(plain).unwrap();
+        assert_eq!(state.discards[0].last(), Some(&plain));
+        assert!(state.hands[0].contains(&red));
+    }
+
+    #[test]
+    fn test_riichi_with_a_copy_not_held_changes_nothing() {
+        let mut engine = riichi_setup();
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 27]);
+        engine.state.drawn_tile[0] = Some(Tile::new(4).unwrap());
+        engine.update_win_flags();
+        let before = state_json(&engine);
+        let since = engine.events_since(0).len();
+
+        // Only plain 5m are held, so riichi on a red one is refused outright
+        assert!(engine.player_riichi(Tile::red_five(4).unwrap()).is_err());
+        assert_eq!(state_json(&engine), before);
+        assert_eq!(engine.pending_riichi, None);
+        assert!(engine.events_since(since).is_empty());
+
+        // The hand stays unlocked for an ordinary discard
+        assert!(engine.player_discard(Tile::new(0).unwrap()).is_ok());
+        assert!(!engine.state.riichi[0]);
+    }
+
+    #[test]
+    fn test_chi_with_the_chosen_copy() {
+        let red = Tile::red_five(4).unwrap();
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = vec![Tile::new(3).unwrap(), Tile::new(4).unwrap(), red, Tile::new(31).unwrap()];
+
+        cpu_discards(&mut engine, Player::Cpu3, 5);
+        assert_eq!(engine.state.phase, GamePhase::Call);
+        let state = engine.player_chi(&[red, Tile::new(3).unwrap()]).unwrap();
+        assert!(state.melds[0][0].tiles.contains(&red));
+        assert_eq!(state.hands[0], tiles(&[4, 31]));
+
+        // A copy the hand does not hold cannot be called with
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[3, 4, 31]);
+        cpu_discards(&mut engine, Player::Cpu3, 5);
+        assert_eq!(engine.player_chi(&[red, Tile::new(3).unwrap()]).err().unwrap(), "Tile not found in hand");
+    }
+
+    #[test]
+    fn test_red_fives_follow_rules() {
+        let mut engine = GameEngine::new();
+        engine.start_hand(HandSetup::with_rules(RuleConfig { red_fives: RedFives::Zero, ..RuleConfig::default() }));
+        let state = engine.get_state();
+        let dealt = state.hands.iter().flatten().chain(state.drawn_tile.iter().flatten());
+        assert!(!dealt.chain(engine.wall.iter()).any(|tile| tile.red));
+        assert_eq!(state.rules.red_fives, RedFives::Zero);
+    }
+
+    #[test]
+    fn test_starting_points_follow_rules() {
+        let mut engine = GameEngine::new();
+        let state = engine.start_hand(HandSetup::with_rules(RuleConfig { starting_points: 30000, ..RuleConfig::default() }));
+        assert_eq!(state.scores, [30000; 4]);
+    }
+
+    /// Noten hand with no East, for abortive draw tests
+    const NOTEN: [u8; 13] = [0, 2, 4, 9, 11, 13, 18, 20, 22, 28, 29, 31, 33];
+
+    /// Every seat discards East on the first go-around
+    fn four_winds_engine(abortive_draws: AbortiveDraws) -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine.start_hand(HandSetup::with_rules(RuleConfig { abortive_draws, ..RuleConfig::default() }));
+        engine.state.hands = std::array::from_fn(|_| tiles(&NOTEN));
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.player_discard(Tile::new(27).unwrap()).unwrap();
+        for seat in [Player::Cpu1, Player::Cpu2, Player::Cpu3] {
+            cpu_discards(&mut engine, seat, 27);
+        }
+        engine
+    }
+
+    #[test]
+    fn test_four_winds_abortive_draw() {
+        let engine = four_winds_engine(AbortiveDraws::default());
+        assert_eq!(engine.state.phase, GamePhase::End);
+        assert_eq!(engine.state.result, Some(GameResult::draw(WinType::FourWinds)));
+        assert_eq!(engine.state.scores, [STARTING_POINTS; 4]);
+
+        let engine = four_winds_engine(AbortiveDraws { four_winds: false, ..AbortiveDraws::default() });
+        assert_eq!(engine.state.phase, GamePhase::Draw);
+        assert_eq!(engine.state.current_player, Player::Player);
+    }
+
+    #[test]
+    fn test_four_riichi_abortive_draw() {
+        let mut engine = riichi_setup();
+        engine.state.riichi = [false, true, true, true];
+
+        let state = engine.player_riichi(Tile::new(27).unwrap()).unwrap();
+        assert_eq!(state.phase, GamePhase::End);
+        assert_eq!(state.result.clone().unwrap().win_type, WinType::FourRiichi);
+        // The fourth stick is paid and stays on the table
+        assert_eq!(state.riichi_sticks, 1);
+    }
+
+    fn ankan(id: u8) -> Meld {
+        Meld { kind: MeldKind::Ankan, tiles: tiles(&[id; 4]), called_tile: None, from: None }
+    }
+
+    #[test]
+    fn test_four_kans_abortive_draw() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands = std::array::from_fn(|_| tiles(&NOTEN));
+        engine.state.melds[1] = vec![ankan(5), ankan(6)];
+        engine.state.melds[2] = vec![ankan(14), ankan(15)];
+        engine.state.drawn_tile[0] = Some(Tile::new(3).unwrap());
+
+        let state = engine.player_discard(Tile::new(3).unwrap()).unwrap();
+        assert_eq!(state.result.clone().unwrap().win_type, WinType::FourKans);
+    }
+
+    #[test]
+    fn test_four_kans_by_one_seat_continue() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands = std::array::from_fn(|_| tiles(&NOTEN));
+        engine.state.hands[1] = tiles(&[30]);
+        engine.state.melds[1] = vec![ankan(5), ankan(6), ankan(14), ankan(15)];
+        engine.state.drawn_tile[0] = Some(Tile::new(3).unwrap());
+
+        let state = engine.player_discard(Tile::new(3).unwrap()).unwrap();
+        assert_eq!(state.phase, GamePhase::Draw);
+    }
+
+    /// Cpu1-Cpu3 all wait on 6s with tanyao; the player deals in with it
+    fn triple_ron_engine(setup: HandSetup) -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine.start_hand(setup);
+        engine.dead_wall = DeadWall::default();
+        engine.state.hands = std::array::from_fn(|_| tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23]));
+        engine.state.hands[0] = tiles(&NOTEN);
+        engine.state.drawn_tile[0] = Some(Tile::new(23).unwrap());
+        engine.player_discard(Tile::new(23).unwrap()).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_triple_ron_abortive_draw() {
+        let mut engine = triple_ron_engine(HandSetup::default());
+        assert_eq!(engine.state.phase, GamePhase::Ron);
+        assert_eq!(engine.state.current_player, Player::Cpu1);
+
+        let state = engine.cpu_step().unwrap();
+        assert_eq!(state.result, Some(GameResult::draw(WinType::TripleRon)));
+
+        // Without the abort, head bump gives the win to the next seat only
+        let mut engine = triple_ron_engine(HandSetup::with_rules(RuleConfig {
+            abortive_draws: AbortiveDraws { triple_ron: false, ..AbortiveDraws::default() },
+            ..RuleConfig::default()
+        }));
+        let state = engine.cpu_step().unwrap();
+        assert_eq!(state.result.clone().unwrap().winner, Some(Player::Cpu1));
+        assert!(state.additional_results.is_empty());
+    }
+
+    #[test]
+    fn test_triple_ron_with_double_ron_pays_everyone() {
+        let mut engine = triple_ron_engine(HandSetup::with_rules(RuleConfig {
+            abortive_draws: AbortiveDraws { triple_ron: false, ..AbortiveDraws::default() },
+            ron_resolution: RonResolution::DoubleRon,
+            ..RuleConfig::default()
+        }));
+        let state = engine.cpu_step().unwrap();
+        let winners: Vec<_> = state.result.iter().chain(&state.additional_results).map(|r| r.winner).collect();
+        assert_eq!(winners, vec![Some(Player::Cpu1), Some(Player::Cpu2), Some(Player::Cpu3)]);
+    }
+
+    /// Cpu3 discards 6s into the tanyao waits of the player and Cpu2
+    fn double_ron_engine(setup: HandSetup) -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine.start_hand(setup);
+        engine.dead_wall = DeadWall::default();
+        let waiting = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23]);
+        engine.state.hands = [waiting.clone(), tiles(&NOTEN), waiting, Vec::new()];
+        engine.state.drawn_tile[0] = None;
+        cpu_discards(&mut engine, Player::Cpu3, 23);
+        engine
+    }
+
+    #[test]
+    fn test_head_bump_goes_to_next_seat_from_discarder() {
+        // The player sits right after Cpu3, so the player's ron takes priority over Cpu2
+        let mut engine = double_ron_engine(HandSetup::default());
+        assert_eq!(engine.state.phase, GamePhase::Ron);
+        assert_eq!(engine.state.current_player, Player::Player);
+        let state = engine.player_ron().unwrap();
+        assert_eq!(state.result.clone().unwrap().winner, Some(Player::Player));
+        assert!(state.additional_results.is_empty());
+
+        // Passing lets Cpu2 win instead
+        let mut engine = double_ron_engine(HandSetup::default());
+        let state = engine.player_pass().unwrap();
+        assert_eq!(state.phase, GamePhase::End);
+        assert_eq!(state.result.clone().unwrap().winner, Some(Player::Cpu2));
+    }
+
+    #[test]
+    fn test_double_ron_pays_both_winners() {
+        let mut engine = double_ron_engine(HandSetup {
+            honba: 1,
+            riichi_sticks: 1,
+            ..HandSetup::with_rules(RuleConfig { ron_resolution: RonResolution::DoubleRon, ..RuleConfig::default() })
+        });
+        let state = engine.player_ron().unwrap();
+        let first = state.result.clone().unwrap();
+        let second = state.additional_results[0].clone();
+        assert_eq!(first.winner, Some(Player::Player));
+        assert_eq!(second.winner, Some(Player::Cpu2));
+        assert_eq!(second.deal_in, Some(Player::Cpu3));
+        // Tanyao 1 han 40 fu: the dealer (player) also takes 300 honba and the stick, Cpu2 only its hand
+        assert_eq!(first.point_changes, [3300, 0, 0, -2300]);
+        assert_eq!(second.point_changes, [0, 0, 1300, -1300]);
+        assert_eq!(state.scores, [28300, 25000, 26300, 21400]);
+        assert_eq!(state.riichi_sticks, 0);
+    }
+
+    #[test]
+    fn test_player_kyuushu() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        // 1m 9m 1p 9p 1s 9s E S W: nine different terminals and honors
+        engine.state.hands[0] = tiles(&[0, 8, 9, 17, 18, 26, 27, 28, 29, 2, 3, 4, 5]);
+        engine.state.drawn_tile[0] = Some(Tile::new(10).unwrap());
+        engine.update_win_flags();
+        assert!(engine.state.can_kyuushu[0]);
+
+        let state = engine.player_kyuushu().unwrap();
+        assert_eq!(state.phase, GamePhase::End);
+        assert_eq!(state.result, Some(GameResult::draw(WinType::NineTerminals)));
+    }
+
+    #[test]
+    fn test_kyuushu_only_on_first_draw_and_when_enabled() {
+        let mut engine = GameEngine::new();
+        engine.start_hand(HandSetup::with_rules(RuleConfig {
+            abortive_draws: AbortiveDraws { nine_terminals: false, ..AbortiveDraws::default() },
+            ..RuleConfig::default()
+        }));
+        engine.state.hands[0] = tiles(&[0, 8, 9, 17, 18, 26, 27, 28, 29, 2, 3, 4, 5]);
+        engine.state.drawn_tile[0] = Some(Tile::new(10).unwrap());
+        engine.update_win_flags();
+        assert!(!engine.state.can_kyuushu[0]);
+        assert!(engine.player_kyuushu().is_err());
+
+        engine.state.rules.abortive_draws = AbortiveDraws::default();
+        engine.state.discards[0] = tiles(&[33]);
+        engine.update_win_flags();
+        assert!(!engine.state.can_kyuushu[0]);
+    }
+
+    #[test]
+    fn test_cpu_declares_kyuushu() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.current_player = Player::Cpu1;
+        engine.state.drawn_tile = [None, Some(Tile::new(10).unwrap()), None, None];
+        engine.state.hands[1] = tiles(&[0, 8, 9, 17, 18, 26, 27, 28, 29, 2, 3, 4, 5]);
+        engine.update_win_flags();
+
+        let state = engine.cpu_step().unwrap();
+        assert_eq!(state.result.clone().unwrap().win_type, WinType::NineTerminals);
+    }
+
+    #[test]
+    fn test_events_record_deal_draw_and_discard() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        let indicator = engine.state.dora_indicators[0];
+        let drawn = engine.state.drawn_tile[0].unwrap();
+        engine.player_discard(drawn).unwrap();
+
+        assert_eq!(
+            engine.events_since(0)[..4],
+            [
+                GameEvent::Deal { dealer: Player::Player, round_wind: Wind::East, honba: 0 },
+                GameEvent::DoraReveal { indicator },
+                GameEvent::Draw { seat: Player::Player, tile: Some(drawn), rinshan: false },
+                GameEvent::Discard { seat: Player::Player, tile: drawn, tsumogiri: true },
+            ]
+        );
+        assert_eq!(engine.events_since(2).len(), engine.events_since(0).len() - 2);
+        assert!(engine.events_since(1000).is_empty());
+
+        // A new game starts a new log
+        engine.new_game(Some(TEST_SEED));
+        assert_eq!(engine.events_since(0).len(), 3);
+    }
+
+    #[test]
+    fn test_events_record_riichi_before_discard() {
+        let mut engine = riichi_setup();
+        let since = engine.events_since(0).len();
+        engine.player_riichi(Tile::new(27).unwrap()).unwrap();
+        assert_eq!(
+            engine.events_since(since)[..2],
+            [
+                GameEvent::Riichi { seat: Player::Player, open: false },
+                GameEvent::Discard { seat: Player::Player, tile: Tile::new(27).unwrap(), tsumogiri: true },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_record_kan_replacement_and_dora() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[4, 4, 4, 9, 10, 11, 12, 13, 14, 18, 19, 20, 31]);
+        engine.state.drawn_tile[0] = Some(Tile::new(4).unwrap());
+        engine.update_win_flags();
+        let since = engine.events_since(0).len();
+
+        let state = engine.player_kan(Tile::new(4).unwrap()).unwrap().clone();
+        assert_eq!(
+            engine.events_since(since),
+            [
+                GameEvent::Call { seat: Player::Player, kind: MeldKind::Ankan, tiles: tiles(&[4, 4, 4, 4]), from: None },
+                GameEvent::Draw { seat: Player::Player, tile: state.drawn_tile[0], rinshan: true },
+                GameEvent::DoraReveal { indicator: state.dora_indicators[1] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_record_tsumo_and_score() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.dead_wall = DeadWall::default();
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.discards[0] = tiles(&[33]); // Past the first draw, so no tenhou
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        engine.update_win_flags();
+        let since = engine.events_since(0).len();
+
+        engine.player_tsumo().unwrap();
+        assert_eq!(
+            engine.events_since(since),
+            [
+                GameEvent::Tsumo { winner: Player::Player, tile: Tile::new(27).unwrap() },
+                GameEvent::Score { point_changes: [6000, -2000, -2000, -2000], scores: [31000, 23000, 23000, 23000] },
+            ]
+        );
+    }
+
+    /// Practice game where the player has drawn and may discard
+    fn practice_engine() -> GameEngine {
+        let mut engine = GameEngine::new();
+        engine.start_hand(HandSetup {
+            seed: Some(TEST_SEED),
+            ..HandSetup::with_rules(RuleConfig { practice: true, ..RuleConfig::default() })
+        });
+        engine
+    }
+
+    /// Discard the drawn tile and play on until the player can discard again
+    fn discard_drawn(engine: &mut GameEngine) {
+        let drawn = engine.state.drawn_tile[0].unwrap();
+        engine.apply(Action::PlayerDiscard { tile: drawn }).unwrap();
+        while engine.state.phase != GamePhase::End
+            && !(engine.state.current_player == Player::Player && engine.state.phase == GamePhase::Discard)
+        {
+            let action = match (engine.state.current_player, engine.state.phase) {
+                (Player::Player, GamePhase::Draw) => Action::PlayerDraw,
+                (Player::Player, _) => Action::PlayerPass,
+                _ => Action::CpuStep,
+            };
+            engine.apply(action).unwrap();
+        }
+    }
+
+    fn state_json(engine: &GameEngine) -> String {
+        serde_json::to_string(&engine.get_state()).unwrap()
+    }
+
+    #[test]
+    fn test_undo_needs_practice_mode() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        discard_drawn(&mut engine);
+        assert_eq!(engine.undo().err().unwrap(), "Undo is only available in practice mode");
+
+        let mut engine = practice_engine();
+        assert_eq!(engine.undo().err().unwrap(), "No discard to undo");
+    }
+
+    #[test]
+    fn test_undo_reverts_cpu_turns() {
+        let mut engine = practice_engine();
+        let before = state_json(&engine);
+        let events = engine.events_since(0).len();
+        discard_drawn(&mut engine);
+        let after = state_json(&engine);
+        assert_ne!(after, before);
+
+        engine.undo().unwrap();
+        assert_eq!(state_json(&engine), before);
+        assert_eq!(engine.events_since(0).len(), events);
+        assert!(engine.game_record().actions.is_empty());
+
+        // The CPUs replay the same turns when the player repeats the discard
+        discard_drawn(&mut engine);
+        assert_eq!(state_json(&engine), after);
+    }
+
+    #[test]
+    fn test_record_is_withheld_until_the_game_ends() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        discard_drawn(&mut engine);
+        assert!(engine.finished_record().is_err());
+
+        engine.state.phase = GamePhase::End;
+        assert_eq!(engine.finished_record().unwrap(), engine.game_record());
+    }
+
+    #[test]
+    fn test_undo_steps_back_one_discard_at_a_time() {
+        let mut engine = practice_engine();
+        let first = state_json(&engine);
+        discard_drawn(&mut engine);
+        let second = state_json(&engine);
+        discard_drawn(&mut engine);
+
+        engine.undo().unwrap();
+        assert_eq!(state_json(&engine), second);
+        engine.undo().unwrap();
+        assert_eq!(state_json(&engine), first);
+        assert!(engine.undo().is_err());
+    }
+
+    #[test]
+    fn test_shanten_counts_drawn_tile() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands[0] = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27]);
+        engine.state.drawn_tile[0] = None;
+        assert_eq!(engine.shanten(Player::Player), 0);
+        engine.state.drawn_tile[0] = Some(Tile::new(27).unwrap());
+        assert_eq!(engine.shanten(Player::Player), -1);
+    }
+
+    #[test]
+    fn test_waits_count_visible_tiles() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        engine.state.hands = Default::default();
+        engine.state.discards = Default::default();
+        engine.state.melds = Default::default();
+        engine.state.dora_indicators = tiles(&[9]);
+        engine.state.drawn_tile = [None; 4];
+        // 2345m 789m 123p EEE waits on 2m and 5m
+        engine.state.hands[0] = tiles(&[1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 27, 27, 27]);
+        engine.state.discards[2] = tiles(&[4]);
+        // Cpu1 pons that 5m: the pond and the meld share one tile
+        engine.state.melds[1].push(Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[4, 4, 4]),
+            called_tile: Some(Tile::new(4).unwrap()),
+            from: Some(Player::Cpu2),
+        });
+        engine.update_win_flags();
+
+        assert_eq!(engine.visible_counts(Player::Player)[9], 2);
+        assert_eq!(
+            engine.state.waits,
+            vec![
+                Wait { tile: Tile::new(1).unwrap(), remaining: 3 },
+                Wait { tile: Tile::new(4).unwrap(), remaining: 0 },
+            ]
+        );
+
+        engine.state.hands[0] = tiles(&[0, 3, 6, 9, 12, 15, 18, 21, 24, 27, 28, 29, 30]);
+        engine.update_win_flags();
+        assert!(engine.state.waits.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_discards_needs_a_discard_turn() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(TEST_SEED));
+        let options = engine.analyze_discards(Player::Player).unwrap();
+        assert!(!options.is_empty());
+        let shanten = engine.shanten(Player::Player);
+        assert!(options.iter().all(|option| option.shanten >= shanten));
+
+        engine.state.drawn_tile[0] = None;
+        assert_eq!(engine.analyze_discards(Player::Player).err().unwrap(), "No discard to analyze");
+    }
+}
@@ -0,0 +1,306 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::game_engine::{random_seed, GameEngine, HandSetup};
+use crate::models::game_state::{GamePhase, GameState, Player, Wind, RIICHI_STICK};
+use crate::models::match_state::{rank_seats, MatchState};
+use crate::models::record::Action;
+use crate::models::rules::RuleConfig;
+
+/// Runs a match as a series of hands on a `GameEngine`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatchEngine {
+    state: Option<MatchState>,
+}
+
+impl MatchEngine {
+    pub fn new() -> Self {
+        MatchEngine { state: None }
+    }
+
+    /// Start a match under `rules` with the player dealing the first hand.
+    /// The rules stay with every hand's state for the rest of the match, and `seed`
+    /// (fresh if not given) drives every hand's wall.
+    pub fn start_match<'a>(&mut self, engine: &'a mut GameEngine, rules: RuleConfig, seed: Option<u64>) -> &'a GameState {
+        let state = MatchState::new(rules.match_length, Player::Player);
+        self.state = Some(state.clone());
+        engine.start_hand(HandSetup {
+            match_state: Some(state),
+            seed: Some(seed.unwrap_or_else(random_seed)),
+            ..HandSetup::with_rules(rules)
+        })
+    }
+
+    /// Move on from a finished hand: rotate the dealer or repeat, then deal the next hand.
+    /// After the last hand the match is finished and the final ranking recorded instead.
+    pub fn next_hand<'a>(&mut self, engine: &'a mut GameEngine) -> Result<&'a GameState, String> {
+        let Some(state) = self.state.as_mut() else {
+            return Err("No match in progress".into());
+        };
+        if state.finished {
+            return Err("Match has finished".into());
+        }
+        let hand = engine.get_state();
+        if hand.phase != GamePhase::End {
+            return Err("Hand is still in progress".into());
+        }
+
+        let winner = hand.result.as_ref().and_then(|result| result.winner);
+        let dealer_keeps = match winner {
+            Some(winner) => {
+                // Under double ron the dealer keeps the deal when among the winners
+                winner == hand.dealer || hand.additional_results.iter().any(|result| result.winner == Some(hand.dealer))
+            }
+            // The dealer repeats after an abortive draw, or an exhaustive draw in tenpai
+            None => hand
+                .result
+                .as_ref()
+                .is_some_and(|result| result.win_type.is_abortive_draw() || result.dealer_tenpai),
+        };
+
+        if dealer_keeps {
+            state.honba += 1;
+        } else {
+            state.honba = if winner.is_none() { state.honba + 1 } else { 0 };
+            state.dealer = state.dealer.next();
+            if state.dealer == state.first_dealer {
+                state.round_wind = Wind::from_index(state.round_wind.index() + 1);
+                state.hand_number = 1;
+            } else {
+                state.hand_number += 1;
+            }
+        }
+
+        engine.record_action(Action::NextHand);
+        let mut scores = hand.scores;
+        let past_last_round = state.round_wind.index() > state.length.last_round().index();
+        let busted = hand.rules.busting && scores.iter().any(|&points| points < 0);
+        if past_last_round || busted {
+            state.finished = true;
+            state.ranking = rank_seats(&scores, state.first_dealer);
+            // Sticks left on the table go to first place
+            scores[state.ranking[0].index()] += hand.riichi_sticks as i32 * RIICHI_STICK;
+            return Ok(engine.finish_match(state.clone(), scores));
+        }
+
+        Ok(engine.start_hand(HandSetup {
+            dealer: state.dealer,
+            round_wind: state.round_wind,
+            honba: state.honba,
+            riichi_sticks: hand.riichi_sticks,
+            scores,
+            match_state: Some(state.clone()),
+            rules: hand.rules,
+            seed: None,
+        }))
+    }
+
+    pub fn state(&self) -> Option<&MatchState> {
+        self.state.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_state::{GameResult, WinType};
+    use crate::models::match_state::MatchLength;
+    use crate::models::rules::RonResolution;
+    use crate::models::tile::Tile;
+
+    /// End the current hand with `result`
+    fn end_hand(engine: &mut GameEngine, result: GameResult) {
+        let state = engine.state_mut();
+        state.result = Some(result);
+        state.phase = GamePhase::End;
+    }
+
+    fn rules(match_length: MatchLength) -> RuleConfig {
+        RuleConfig { match_length, ..RuleConfig::default() }
+    }
+
+    fn win(winner: Player) -> GameResult {
+        GameResult::tsumo(winner, Tile::new(0).unwrap())
+    }
+
+    #[test]
+    fn test_start_match() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        let state = match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+        let match_state = state.match_state.clone().unwrap();
+        assert_eq!(match_state.hand_number, 1);
+        assert_eq!(match_state.round_wind, Wind::East);
+        assert_eq!(state.dealer, Player::Player);
+        assert!(match_engine.next_hand(&mut engine).is_err());
+    }
+
+    #[test]
+    fn test_dealer_win_is_renchan() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+
+        end_hand(&mut engine, win(Player::Player));
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert_eq!(state.dealer, Player::Player);
+        assert_eq!(state.honba, 1);
+        assert_eq!(state.current_player, Player::Player);
+    }
+
+    #[test]
+    fn test_non_dealer_win_rotates_dealer() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+
+        end_hand(&mut engine, win(Player::Cpu2));
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert_eq!(state.dealer, Player::Cpu1);
+        assert_eq!(state.honba, 0);
+        assert_eq!(state.current_player, Player::Cpu1);
+        assert!(state.drawn_tile[1].is_some());
+        assert_eq!(state.match_state.clone().unwrap().hand_number, 2);
+    }
+
+    #[test]
+    fn test_east_only_match_ends_after_east_four() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+
+        for seat in [Player::Cpu1, Player::Cpu2, Player::Cpu3] {
+            end_hand(&mut engine, win(seat));
+            match_engine.next_hand(&mut engine).unwrap();
+        }
+        assert_eq!(engine.get_state().dealer, Player::Cpu3);
+
+        engine.state_mut().scores = [20000, 35000, 25000, 20000];
+        end_hand(&mut engine, win(Player::Cpu1));
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        let match_state = state.match_state.clone().unwrap();
+        assert!(match_state.finished);
+        assert_eq!(match_state.ranking, vec![Player::Cpu1, Player::Cpu2, Player::Player, Player::Cpu3]);
+        assert!(match_engine.next_hand(&mut engine).is_err());
+    }
+
+    #[test]
+    fn test_east_south_moves_to_south_round() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastSouth), None);
+
+        for seat in [Player::Cpu1, Player::Cpu2, Player::Cpu3, Player::Player] {
+            end_hand(&mut engine, win(seat));
+            match_engine.next_hand(&mut engine).unwrap();
+        }
+        let state = engine.get_state();
+        assert_eq!(state.round_wind, Wind::South);
+        assert_eq!(state.dealer, Player::Player);
+        assert_eq!(state.match_state.unwrap().hand_number, 1);
+    }
+
+    #[test]
+    fn test_leftover_sticks_carry_over() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+
+        // Dealer noten: the dealer passes but the honba still goes up
+        engine.state_mut().riichi_sticks = 2;
+        end_hand(&mut engine, GameResult::exhaustive_draw());
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert_eq!(state.riichi_sticks, 2);
+        assert_eq!(state.honba, 1);
+        assert_eq!(state.dealer, Player::Cpu1);
+    }
+
+    #[test]
+    fn test_dealer_tenpai_draw_is_renchan() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+
+        let mut draw = GameResult::exhaustive_draw();
+        draw.dealer_tenpai = true;
+        end_hand(&mut engine, draw);
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert_eq!(state.dealer, Player::Player);
+        assert_eq!(state.honba, 1);
+    }
+
+    #[test]
+    fn test_dealer_second_in_double_ron_is_renchan() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(
+            &mut engine,
+            RuleConfig { ron_resolution: RonResolution::DoubleRon, ..rules(MatchLength::EastOnly) },
+            None,
+        );
+
+        let tile = Tile::new(0).unwrap();
+        end_hand(&mut engine, GameResult::ron(Player::Cpu2, tile, Player::Cpu1));
+        engine.state_mut().additional_results = vec![GameResult::ron(Player::Player, tile, Player::Cpu1)];
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert_eq!(state.dealer, Player::Player);
+        assert_eq!(state.honba, 1);
+    }
+
+    #[test]
+    fn test_abortive_draw_is_renchan() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), None);
+
+        end_hand(&mut engine, GameResult::draw(WinType::FourWinds));
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert_eq!(state.dealer, Player::Player);
+        assert_eq!(state.honba, 1);
+    }
+
+    #[test]
+    fn test_match_ends_when_a_seat_goes_below_zero() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, rules(MatchLength::EastSouth), None);
+
+        let state = engine.state_mut();
+        state.scores = [-1000, 51000, 25000, 25000];
+        state.riichi_sticks = 1;
+        end_hand(&mut engine, win(Player::Cpu1));
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        let match_state = state.match_state.clone().unwrap();
+        assert!(match_state.finished);
+        assert_eq!(match_state.ranking[0], Player::Cpu1);
+        // Leftover stick goes to first place
+        assert_eq!(state.scores[1], 52000);
+        assert_eq!(state.riichi_sticks, 0);
+    }
+
+    #[test]
+    fn test_no_busting_plays_on_below_zero() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        match_engine.start_match(&mut engine, RuleConfig { busting: false, ..rules(MatchLength::EastSouth) }, None);
+
+        engine.state_mut().scores = [-1000, 51000, 25000, 25000];
+        end_hand(&mut engine, win(Player::Cpu1));
+        let state = match_engine.next_hand(&mut engine).unwrap();
+        assert!(!state.match_state.clone().unwrap().finished);
+        assert_eq!(state.scores[0], -1000);
+    }
+
+    #[test]
+    fn test_seeded_match_replays_every_hand() {
+        let second_hand = || {
+            let mut engine = GameEngine::new();
+            let mut match_engine = MatchEngine::new();
+            match_engine.start_match(&mut engine, rules(MatchLength::EastOnly), Some(99));
+            end_hand(&mut engine, win(Player::Cpu2));
+            match_engine.next_hand(&mut engine).unwrap().clone()
+        };
+        let state = second_hand();
+        assert_eq!(state.seed, 99);
+        assert_eq!(state.hands, second_hand().hands);
+    }
+}
@@ -1,4 +1,11 @@
+pub mod dead_wall;
 pub mod game_engine;
+pub mod match_engine;
+pub mod replay;
+pub mod save;
+pub mod scoring;
+pub mod shanten;
 pub mod win_checker;
+pub mod yaku;
 
 
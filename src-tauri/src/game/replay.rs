@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::game_engine::{GameEngine, HandSetup};
+use crate::game::match_engine::MatchEngine;
+use crate::models::event::GameEvent;
+use crate::models::game_state::GameState;
+use crate::models::record::{Action, GameRecord};
+
+/// Where to move a replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplaySeek {
+    /// After this many actions
+    Position(usize),
+    NextAction,
+    PreviousAction,
+    /// Start of the next turn: after the next discard, or the end of a hand
+    NextTurn,
+    PreviousTurn,
+}
+
+/// The full state after `position` of `length` actions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub position: usize,
+    pub length: usize,
+    pub state: GameState,
+}
+
+/// Plays a recorded game back from its seed, moving to any point by action or turn
+pub struct ReplayEngine {
+    record: GameRecord,
+    /// Positions where a turn starts, in order; always includes 0 and the end
+    turn_starts: Vec<usize>,
+    position: usize,
+    engine: GameEngine,
+    match_engine: MatchEngine,
+}
+
+impl ReplayEngine {
+    /// Load `record`, playing it through once so a record that does not replay is rejected up front
+    pub fn load(record: GameRecord) -> Result<Self, String> {
+        let mut replay = ReplayEngine {
+            record,
+            turn_starts: vec![0],
+            position: 0,
+            engine: GameEngine::new(),
+            match_engine: MatchEngine::new(),
+        };
+        replay.restart();
+        for position in 1..=replay.record.actions.len() {
+            let events = replay.engine.events_since(0).len();
+            replay.step()?;
+            let turn_ended = replay.engine.events_since(events).iter().any(|event| {
+                matches!(
+                    event,
+                    GameEvent::Discard { .. }
+                        | GameEvent::Ron { .. }
+                        | GameEvent::Tsumo { .. }
+                        | GameEvent::ExhaustiveDraw { .. }
+                        | GameEvent::Abort { .. }
+                        | GameEvent::Deal { .. }
+                )
+            });
+            if turn_ended || position == replay.record.actions.len() {
+                replay.turn_starts.push(position);
+            }
+        }
+        replay.turn_starts.dedup();
+        replay.restart();
+        Ok(replay)
+    }
+
+    /// Move to `seek` and return the state there; seeking back plays the game again from the start
+    pub fn seek(&mut self, seek: ReplaySeek) -> Result<ReplayFrame, String> {
+        let length = self.record.actions.len();
+        let target = match seek {
+            ReplaySeek::Position(position) if position > length => {
+                return Err(format!("Replay has only {} actions", length));
+            }
+            ReplaySeek::Position(position) => position,
+            ReplaySeek::NextAction => (self.position + 1).min(length),
+            ReplaySeek::PreviousAction => self.position.saturating_sub(1),
+            ReplaySeek::NextTurn => self.turn_starts.iter().copied().find(|&p| p > self.position).unwrap_or(length),
+            ReplaySeek::PreviousTurn => self.turn_starts.iter().copied().rfind(|&p| p < self.position).unwrap_or(0),
+        };
+
+        if target < self.position {
+            self.restart();
+        }
+        while self.position < target {
+            self.step()?;
+        }
+        Ok(self.frame())
+    }
+
+    /// The state at the current position
+    pub fn frame(&self) -> ReplayFrame {
+        ReplayFrame {
+            position: self.position,
+            length: self.record.actions.len(),
+            state: self.engine.get_state(),
+        }
+    }
+
+    /// Deal the first hand again
+    fn restart(&mut self) {
+        self.engine = GameEngine::new();
+        self.match_engine = MatchEngine::new();
+        let seed = Some(self.record.seed);
+        if self.record.is_match {
+            self.match_engine.start_match(&mut self.engine, self.record.rules, seed);
+        } else {
+            self.engine.start_hand(HandSetup {
+                seed,
+                ..HandSetup::with_rules(self.record.rules)
+            });
+        }
+        self.position = 0;
+    }
+
+    /// Apply the action at the current position
+    fn step(&mut self) -> Result<(), String> {
+        let action = self.record.actions[self.position].clone();
+        let applied = match action {
+            Action::NextHand => self.match_engine.next_hand(&mut self.engine).map(|_| ()),
+            action => self.engine.apply(action).map(|_| ()),
+        };
+        applied.map_err(|e| format!("Action {} does not replay: {}", self.position + 1, e))?;
+        self.position += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_state::{GamePhase, Player};
+    use crate::models::match_state::MatchLength;
+    use crate::models::rules::RuleConfig;
+
+    /// Play the player's drawn tile every turn, passing on every call, for up to `actions` actions
+    fn play(engine: &mut GameEngine, actions: usize) {
+        for _ in 0..actions {
+            let state = engine.get_state();
+            let action = match (state.current_player, state.phase) {
+                (_, GamePhase::End) => break,
+                (Player::Player, GamePhase::Draw) => Action::PlayerDraw,
+                (Player::Player, GamePhase::Discard) => match state.drawn_tile[0] {
+                    Some(tile) => Action::PlayerDiscard { tile },
+                    None => Action::PlayerDiscard { tile: state.hands[0][0] },
+                },
+                (Player::Player, _) => Action::PlayerPass,
+                _ => Action::CpuStep,
+            };
+            engine.apply(action).unwrap();
+        }
+    }
+
+    fn state_json(state: &GameState) -> String {
+        serde_json::to_string(state).unwrap()
+    }
+
+    #[test]
+    fn test_replay_reaches_the_recorded_state() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(7));
+        play(&mut engine, 40);
+        let record = engine.game_record().clone();
+        assert_eq!(record.seed, 7);
+        assert!(!record.actions.is_empty());
+
+        let mut replay = ReplayEngine::load(record.clone()).unwrap();
+        assert_eq!(replay.frame().position, 0);
+        let end = replay.seek(ReplaySeek::Position(record.actions.len())).unwrap();
+        assert_eq!(state_json(&end.state), state_json(&engine.get_state()));
+    }
+
+    #[test]
+    fn test_replay_steps_back_and_forth() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(11));
+        play(&mut engine, 30);
+        let mut replay = ReplayEngine::load(engine.game_record().clone()).unwrap();
+
+        let fifth = replay.seek(ReplaySeek::Position(5)).unwrap();
+        replay.seek(ReplaySeek::NextAction).unwrap();
+        assert_eq!(replay.frame().position, 6);
+        let back = replay.seek(ReplaySeek::PreviousAction).unwrap();
+        assert_eq!(state_json(&back.state), state_json(&fifth.state));
+        assert!(replay.seek(ReplaySeek::Position(1000)).is_err());
+    }
+
+    #[test]
+    fn test_replay_steps_by_turn() {
+        let mut engine = GameEngine::new();
+        engine.new_game(Some(11));
+        play(&mut engine, 30);
+        let mut replay = ReplayEngine::load(engine.game_record().clone()).unwrap();
+
+        // The player's first action is a discard, which ends the turn
+        let frame = replay.seek(ReplaySeek::NextTurn).unwrap();
+        assert_eq!(frame.position, 1);
+        assert_eq!(frame.state.discards[0].len(), 1);
+        // Each turn after that is a draw and a discard
+        let frame = replay.seek(ReplaySeek::NextTurn).unwrap();
+        assert_eq!(frame.state.discards.iter().map(Vec::len).sum::<usize>(), 2);
+        assert_eq!(replay.seek(ReplaySeek::PreviousTurn).unwrap().position, 1);
+        assert_eq!(replay.seek(ReplaySeek::PreviousTurn).unwrap().position, 0);
+    }
+
+    #[test]
+    fn test_replay_match_across_hands() {
+        let mut engine = GameEngine::new();
+        let mut match_engine = MatchEngine::new();
+        let rules = RuleConfig { match_length: MatchLength::EastOnly, ..RuleConfig::default() };
+        match_engine.start_match(&mut engine, rules, Some(3));
+        play(&mut engine, 500);
+        assert_eq!(engine.get_state().phase, GamePhase::End);
+        match_engine.next_hand(&mut engine).unwrap();
+        play(&mut engine, 10);
+        let record = engine.game_record().clone();
+        assert!(record.is_match);
+
+        let mut replay = ReplayEngine::load(record.clone()).unwrap();
+        let end = replay.seek(ReplaySeek::Position(record.actions.len())).unwrap();
+        assert_eq!(state_json(&end.state), state_json(&engine.get_state()));
+    }
+
+    #[test]
+    fn test_broken_record_is_rejected() {
+        let record = GameRecord {
+            seed: 1,
+            actions: vec![Action::PlayerDraw],
+            ..GameRecord::default()
+        };
+        let error = ReplayEngine::load(record).err().unwrap();
+        assert!(error.starts_with("Action 1 does not replay"));
+    }
+}
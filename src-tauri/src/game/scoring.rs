@@ -0,0 +1,431 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::win_checker::MeldShape;
+use crate::game::yaku::{evaluate_all, HandShape, WaitShape, WinContext, Yaku, YakuResult};
+use crate::models::game_state::{Meld, Player, Wind};
+use crate::models::tile::Tile;
+
+/// Total paid by the noten seats at an exhaustive draw
+pub const NOTEN_PENALTY: i32 = 3000;
+
+/// Named limit reached by a hand's han count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitHand {
+    Mangan,       // 5 han (or 3-4 han with enough fu)
+    Haneman,      // 6-7 han
+    Baiman,       // 8-10 han
+    Sanbaiman,    // 11-12 han
+    KazoeYakuman, // 13+ han
+    Yakuman,
+}
+
+/// Points owed to the winner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Payment {
+    /// Paid in full by the player who dealt in
+    Ron { total: u32 },
+    /// Paid by every other seat; `dealer` is what the dealer pays (0 when the winner is dealer)
+    Tsumo { dealer: u32, non_dealer: u32 },
+}
+
+impl Payment {
+    /// Total points received by the winner
+    pub fn total(&self, winner_is_dealer: bool) -> u32 {
+        match *self {
+            Payment::Ron { total } => total,
+            Payment::Tsumo { non_dealer, .. } if winner_is_dealer => non_dealer * 3,
+            Payment::Tsumo { dealer, non_dealer } => dealer + non_dealer * 2,
+        }
+    }
+
+    /// Point change for every seat [Player, Cpu1, Cpu2, Cpu3]
+    /// `deal_in` is the ronned player (None for tsumo)
+    pub fn point_changes(&self, winner: Player, deal_in: Option<Player>, dealer: Player) -> [i32; 4] {
+        let mut changes = [0i32; 4];
+        match (*self, deal_in) {
+            (Payment::Ron { total }, Some(payer)) => {
+                changes[payer.index()] -= total as i32;
+                changes[winner.index()] += total as i32;
+            }
+            (Payment::Tsumo { dealer: dealer_share, non_dealer }, _) => {
+                for index in 0..4 {
+                    if index == winner.index() {
+                        continue;
+                    }
+                    let share = if index == dealer.index() { dealer_share } else { non_dealer };
+                    changes[index] -= share as i32;
+                    changes[winner.index()] += share as i32;
+                }
+            }
+            (Payment::Ron { .. }, None) => {}
+        }
+        changes
+    }
+}
+
+/// Scored value of a winning hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreResult {
+    pub yaku: Vec<Yaku>,
+    /// Han from yaku plus dora
+    pub han: u8,
+    /// Fu after rounding up to the next 10 (25 for seven pairs)
+    pub fu: u8,
+    /// Number of yakuman (0 for a regular hand)
+    pub yakuman: u8,
+    pub dora: DoraCount,
+    pub limit: Option<LimitHand>,
+    /// Base points before the dealer/non-dealer multipliers
+    pub base_points: u32,
+    pub payment: Payment,
+}
+
+impl ScoreResult {
+    /// Point change for every seat [Player, Cpu1, Cpu2, Cpu3]
+    /// `deal_in` is the ronned player (None for tsumo)
+    pub fn point_changes(&self, winner: Player, deal_in: Option<Player>, dealer: Player) -> [i32; 4] {
+        self.payment.point_changes(winner, deal_in, dealer)
+    }
+}
+
+/// Dora tiles held by the winner; each one adds a han once the hand has a yaku
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DoraCount {
+    pub dora: u8,
+    /// Counted only for riichi hands
+    pub ura_dora: u8,
+    /// Red fives
+    pub aka_dora: u8,
+}
+
+impl DoraCount {
+    pub fn total(&self) -> u8 {
+        self.dora + self.ura_dora + self.aka_dora
+    }
+}
+
+/// Score a winning hand; `hand` is the concealed tiles including the winning tile,
+/// `melds` the called melds and `dora` the dora tiles held
+/// Picks the reading worth the most points. Returns None if the hand has no yaku.
+pub fn score_hand(hand: &[Tile], melds: &[Meld], ctx: &WinContext, dora: DoraCount) -> Option<ScoreResult> {
+    evaluate_all(hand, melds, ctx)
+        .into_iter()
+        .map(|reading| score_reading(&reading, melds, ctx, dora))
+        .max_by_key(|score| (score.base_points, score.han, score.fu))
+}
+
+/// Score one yaku reading
+fn score_reading(reading: &YakuResult, melds: &[Meld], ctx: &WinContext, dora: DoraCount) -> ScoreResult {
+    let is_dealer = ctx.seat_wind == Wind::East;
+    let fu = calculate_fu(reading, melds, ctx);
+    let han = if reading.yakuman > 0 { 0 } else { reading.han + dora.total() };
+    let (base_points, limit) = base_points(han, fu, reading.yakuman);
+
+    ScoreResult {
+        yaku: reading.yaku.clone(),
+        han,
+        fu,
+        yakuman: reading.yakuman,
+        dora,
+        limit,
+        base_points,
+        payment: payment(base_points, is_dealer, ctx.is_tsumo),
+    }
+}
+
+/// Fu of a reading, rounded up to the next 10
+pub fn calculate_fu(reading: &YakuResult, melds: &[Meld], ctx: &WinContext) -> u8 {
+    let is_pinfu = reading.yaku.contains(&Yaku::Pinfu);
+    if is_pinfu {
+        return if ctx.is_tsumo { 20 } else { 30 };
+    }
+
+    // Base fu, closed ron bonus and tsumo bonus
+    let mut fu: u32 = 20;
+    if ctx.is_tsumo {
+        fu += 2;
+    } else if reading.closed {
+        fu += 10;
+    }
+
+    // Seven pairs is always 25 fu; thirteen orphans is a yakuman, so fu does not matter
+    let Some(decomposition) = &reading.decomposition else {
+        return if reading.shape == HandShape::SevenPairs { 25 } else { 0 };
+    };
+
+    // Triplets in the concealed part and in called melds
+    for (index, meld) in decomposition.melds.iter().enumerate() {
+        if let MeldShape::Triplet(id) = *meld {
+            let completed_by_ron = !ctx.is_tsumo && reading.win_meld == Some(index);
+            fu += triplet_fu(id, !completed_by_ron, false);
+        }
+    }
+    for meld in melds {
+        if let MeldShape::Triplet(id) = meld.shape() {
+            fu += triplet_fu(id, meld.is_concealed(), meld.is_kan());
+        }
+    }
+
+    // Value pair: dragons, seat wind and round wind (a double wind pair counts twice)
+    let pair = decomposition.pair;
+    if (31..=33).contains(&pair) {
+        fu += 2;
+    }
+    if pair == ctx.seat_wind.tile_id() {
+        fu += 2;
+    }
+    if pair == ctx.round_wind.tile_id() {
+        fu += 2;
+    }
+
+    // Single-tile waits
+    if matches!(reading.wait, WaitShape::Kanchan | WaitShape::Penchan | WaitShape::Tanki) {
+        fu += 2;
+    }
+
+    // An open hand with no fu beyond the base still scores 30
+    if fu == 20 {
+        return 30;
+    }
+
+    fu.div_ceil(10) as u8 * 10
+}
+
+/// Fu for a triplet: 2 for open simples, doubled when concealed and for terminals/honors,
+/// and four times as much for a kan
+fn triplet_fu(id: u8, concealed: bool, is_kan: bool) -> u32 {
+    let mut fu = if concealed { 4 } else { 2 };
+    if id >= 27 || matches!(id % 9, 0 | 8) {
+        fu *= 2;
+    }
+    if is_kan {
+        fu *= 4;
+    }
+    fu
+}
+
+/// Base points and limit for a han/fu count
+fn base_points(han: u8, fu: u8, yakuman: u8) -> (u32, Option<LimitHand>) {
+    if yakuman > 0 {
+        return (8000 * yakuman as u32, Some(LimitHand::Yakuman));
+    }
+    match han {
+        13.. => (8000, Some(LimitHand::KazoeYakuman)),
+        11..=12 => (6000, Some(LimitHand::Sanbaiman)),
+        8..=10 => (4000, Some(LimitHand::Baiman)),
+        6..=7 => (3000, Some(LimitHand::Haneman)),
+        5 => (2000, Some(LimitHand::Mangan)),
+        _ => {
+            let base = fu as u32 * 2u32.pow(2 + han as u32);
+            if base >= 2000 {
+                (2000, Some(LimitHand::Mangan))
+            } else {
+                (base, None)
+            }
+        }
+    }
+}
+
+/// Round a payment up to the next 100 points
+fn round_up_100(points: u32) -> u32 {
+    points.div_ceil(100) * 100
+}
+
+/// Payment table for base points
+fn payment(base_points: u32, is_dealer: bool, is_tsumo: bool) -> Payment {
+    match (is_tsumo, is_dealer) {
+        (false, true) => Payment::Ron { total: round_up_100(base_points * 6) },
+        (false, false) => Payment::Ron { total: round_up_100(base_points * 4) },
+        (true, true) => Payment::Tsumo {
+            dealer: 0,
+            non_dealer: round_up_100(base_points * 2),
+        },
+        (true, false) => Payment::Tsumo {
+            dealer: round_up_100(base_points * 2),
+            non_dealer: round_up_100(base_points),
+        },
+    }
+}
+
+/// Points moved at an exhaustive draw: the noten seats pay 3000 in total, split among the tenpai seats
+pub fn noten_payments(tenpai: &[bool; 4]) -> [i32; 4] {
+    let ready = tenpai.iter().filter(|&&t| t).count() as i32;
+    if ready == 0 || ready == 4 {
+        return [0; 4];
+    }
+    tenpai.map(|t| if t { NOTEN_PENALTY / ready } else { -NOTEN_PENALTY / (4 - ready) })
+}
+
+/// Nagashi mangan is paid like a mangan tsumo by `seat`
+pub fn nagashi_mangan_payments(seat: Player, dealer: Player) -> [i32; 4] {
+    payment(2000, seat == dealer, true).point_changes(seat, None, dealer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_state::MeldKind;
+
+    /// Build tiles from ids
+    fn tiles(ids: &[u8]) -> Vec<Tile> {
+        ids.iter().map(|&id| Tile::new(id).unwrap()).collect()
+    }
+
+    fn ctx(win_id: u8, is_tsumo: bool, seat_wind: Wind) -> WinContext {
+        WinContext::new(Tile::new(win_id).unwrap(), is_tsumo, seat_wind, Wind::East)
+    }
+
+    #[test]
+    fn test_pinfu_tanyao_ron_non_dealer() {
+        // 234m 567m 234p 345s + 66s, ron 4p: 2 han 30 fu
+        let hand = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let score = score_hand(&hand, &[], &ctx(12, false, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(score.han, 2);
+        assert_eq!(score.fu, 30);
+        assert_eq!(score.payment, Payment::Ron { total: 2000 });
+    }
+
+    #[test]
+    fn test_pinfu_tsumo_dealer() {
+        // Menzen tsumo + pinfu + tanyao: 3 han 20 fu, 1300 all
+        let hand = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let score = score_hand(&hand, &[], &ctx(12, true, Wind::East), DoraCount::default()).unwrap();
+        assert_eq!(score.han, 3);
+        assert_eq!(score.fu, 20);
+        assert_eq!(score.payment, Payment::Tsumo { dealer: 0, non_dealer: 1300 });
+        assert_eq!(score.payment.total(true), 3900);
+    }
+
+    #[test]
+    fn test_fu_for_concealed_terminal_triplet_and_kanchan() {
+        // Riichi, 111m 234p 567s 789s + 55p, ron 8s as a kanchan: 20 + 10 + 8 + 2 = 40 fu
+        let hand = tiles(&[0, 0, 0, 10, 11, 12, 22, 23, 24, 24, 25, 26, 13, 13]);
+        let mut ctx = ctx(25, false, Wind::South);
+        ctx.is_riichi = true;
+        let score = score_hand(&hand, &[], &ctx, DoraCount::default()).unwrap();
+        assert_eq!(score.fu, 40);
+        assert_eq!(score.han, 1);
+        assert_eq!(score.payment, Payment::Ron { total: 1300 });
+    }
+
+    #[test]
+    fn test_dora_reaches_mangan() {
+        // Pinfu tanyao + 3 dora = 5 han
+        let hand = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let dora = DoraCount { dora: 3, ..Default::default() };
+        let score = score_hand(&hand, &[], &ctx(12, false, Wind::South), dora).unwrap();
+        assert_eq!(score.limit, Some(LimitHand::Mangan));
+        assert_eq!(score.payment, Payment::Ron { total: 8000 });
+
+        let score = score_hand(&hand, &[], &ctx(12, false, Wind::East), dora).unwrap();
+        assert_eq!(score.payment, Payment::Ron { total: 12000 });
+    }
+
+    #[test]
+    fn test_four_han_forty_fu_is_mangan() {
+        let (base, limit) = base_points(4, 40, 0);
+        assert_eq!(base, 2000);
+        assert_eq!(limit, Some(LimitHand::Mangan));
+
+        let (base, limit) = base_points(3, 30, 0);
+        assert_eq!(base, 960);
+        assert_eq!(limit, None);
+    }
+
+    #[test]
+    fn test_limit_hands() {
+        assert_eq!(base_points(6, 30, 0), (3000, Some(LimitHand::Haneman)));
+        assert_eq!(base_points(8, 30, 0), (4000, Some(LimitHand::Baiman)));
+        assert_eq!(base_points(11, 30, 0), (6000, Some(LimitHand::Sanbaiman)));
+        assert_eq!(base_points(13, 30, 0), (8000, Some(LimitHand::KazoeYakuman)));
+        assert_eq!(base_points(0, 30, 2), (16000, Some(LimitHand::Yakuman)));
+    }
+
+    #[test]
+    fn test_yakuman_tsumo_non_dealer() {
+        // Daisangen tsumo: 8000/16000
+        let hand = tiles(&[31, 31, 31, 32, 32, 32, 33, 33, 33, 0, 1, 2, 17, 17]);
+        let score = score_hand(&hand, &[], &ctx(0, true, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(score.payment, Payment::Tsumo { dealer: 16000, non_dealer: 8000 });
+        assert_eq!(score.payment.total(false), 32000);
+    }
+
+    #[test]
+    fn test_point_changes() {
+        let hand = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let ron = score_hand(&hand, &[], &ctx(12, false, Wind::South), DoraCount::default()).unwrap();
+        let changes = ron.point_changes(Player::Cpu1, Some(Player::Cpu3), Player::Player);
+        assert_eq!(changes, [0, 2000, 0, -2000]);
+
+        // Non-dealer tsumo: 3 han 20 fu is 700/1300
+        let tsumo = score_hand(&hand, &[], &ctx(12, true, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(tsumo.payment, Payment::Tsumo { dealer: 1300, non_dealer: 700 });
+        let changes = tsumo.point_changes(Player::Cpu1, None, Player::Player);
+        assert_eq!(changes, [-1300, 2700, -700, -700]);
+    }
+
+    #[test]
+    fn test_seven_pairs_is_25_fu() {
+        // Chiitoitsu + tanyao ron: 3 han 25 fu = 3200
+        let hand = tiles(&[1, 1, 4, 4, 10, 10, 15, 15, 21, 21, 23, 23, 6, 6]);
+        let score = score_hand(&hand, &[], &ctx(6, false, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(score.fu, 25);
+        assert_eq!(score.han, 3);
+        assert_eq!(score.payment, Payment::Ron { total: 3200 });
+    }
+
+    #[test]
+    fn test_open_hand_fu() {
+        // Pon of White + 234m 567p 789s + 55m ron on 5m: 20 + 4 (open honor pon) + 2 (tanki) = 30
+        let white_pon = Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[31, 31, 31]),
+            called_tile: Some(Tile::new(31).unwrap()),
+            from: Some(Player::Cpu1),
+        };
+        let hand = tiles(&[1, 2, 3, 13, 14, 15, 24, 25, 26, 4, 4]);
+        let score = score_hand(&hand, std::slice::from_ref(&white_pon), &ctx(4, false, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(score.fu, 30);
+        assert_eq!(score.han, 1);
+        assert_eq!(score.payment, Payment::Ron { total: 1000 });
+
+        // Open kan of White: 20 + 16 + 2 = 38 -> 40
+        let white_kan = Meld {
+            kind: MeldKind::Daiminkan,
+            tiles: tiles(&[31, 31, 31, 31]),
+            ..white_pon
+        };
+        let score = score_hand(&hand, &[white_kan], &ctx(4, false, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(score.fu, 40);
+    }
+
+    #[test]
+    fn test_open_pinfu_shape_is_30_fu() {
+        // Chi 234m + 567m 234p 345s + 66s ron on 4p (ryanmen): tanyao, 20 fu raised to 30
+        let chi = Meld {
+            kind: MeldKind::Chi,
+            tiles: tiles(&[1, 2, 3]),
+            called_tile: Some(Tile::new(1).unwrap()),
+            from: Some(Player::Cpu3),
+        };
+        let hand = tiles(&[4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let score = score_hand(&hand, &[chi], &ctx(12, false, Wind::South), DoraCount::default()).unwrap();
+        assert_eq!(score.fu, 30);
+        assert_eq!(score.yaku, vec![Yaku::Tanyao]);
+    }
+
+    #[test]
+    fn test_noten_payments() {
+        assert_eq!(noten_payments(&[true, false, false, false]), [3000, -1000, -1000, -1000]);
+        assert_eq!(noten_payments(&[true, false, true, false]), [1500, -1500, 1500, -1500]);
+        assert_eq!(noten_payments(&[false, true, true, true]), [-3000, 1000, 1000, 1000]);
+        assert_eq!(noten_payments(&[true; 4]), [0; 4]);
+        assert_eq!(noten_payments(&[false; 4]), [0; 4]);
+    }
+
+    #[test]
+    fn test_nagashi_mangan_payments() {
+        assert_eq!(nagashi_mangan_payments(Player::Cpu2, Player::Player), [-4000, -2000, 8000, -2000]);
+        assert_eq!(nagashi_mangan_payments(Player::Player, Player::Player), [12000, -4000, -4000, -4000]);
+    }
+}
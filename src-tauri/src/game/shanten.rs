@@ -0,0 +1,287 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::win_checker::{count_tiles, Wait};
+use crate::models::game_state::Meld;
+use crate::models::tile::Tile;
+
+/// Tiles away from tenpai: 0 is tenpai, -1 a complete hand.
+/// `counts` are the concealed tiles (13 or 14 less 3 per called meld) and
+/// `called_melds` the melds already called, which count toward the four melds.
+/// Seven pairs and thirteen orphans only apply to closed hands.
+pub fn shanten(counts: &[u8; 34], called_melds: usize) -> i8 {
+    let normal = normal_shanten(counts, called_melds);
+    if called_melds > 0 {
+        return normal;
+    }
+    normal.min(seven_pairs_shanten(counts)).min(thirteen_orphans_shanten(counts))
+}
+
+/// Shanten of a concealed hand plus its called melds
+pub fn hand_shanten(hand: &[Tile], melds: &[Meld]) -> i8 {
+    shanten(&count_tiles(hand), melds.len())
+}
+
+/// Shanten towards four melds and a pair
+pub fn normal_shanten(counts: &[u8; 34], called_melds: usize) -> i8 {
+    let blocks = 4 - called_melds.min(4) as i8;
+    let mut counts = *counts;
+    let mut best = 2 * blocks;
+    search(&mut counts, 0, blocks, 0, 0, false, &mut best);
+    best
+}
+
+/// Shanten towards seven distinct pairs; every pair needs a tile kind of its own
+pub fn seven_pairs_shanten(counts: &[u8; 34]) -> i8 {
+    let pairs = counts.iter().filter(|&&c| c >= 2).count() as i8;
+    let kinds = counts.iter().filter(|&&c| c > 0).count() as i8;
+    6 - pairs + (7 - kinds).max(0)
+}
+
+/// Shanten towards one of each terminal and honor plus a pair of one of them
+pub fn thirteen_orphans_shanten(counts: &[u8; 34]) -> i8 {
+    let orphans = (0..34).filter(|&id| is_orphan(id));
+    let kinds = orphans.clone().filter(|&id| counts[id] > 0).count() as i8;
+    let has_pair = orphans.clone().any(|id| counts[id] >= 2);
+    13 - kinds - has_pair as i8
+}
+
+/// How a hand stands after one discard
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscardOption {
+    pub tile: Tile,
+    pub shanten: i8,
+    /// Tiles that would lower the shanten (ukeire), with the copies not yet seen
+    pub ukeire: Vec<Wait>,
+    /// Unseen copies over all of `ukeire`
+    pub ukeire_count: u32,
+}
+
+/// Every distinct discard from a hand of 14 tiles (less 3 per called meld), best first:
+/// lowest shanten, then most unseen improving tiles.
+/// `visible` are the tiles the player can see, their own hand included.
+pub fn analyze_discards(hand: &[Tile], called_melds: usize, visible: &[u8; 34]) -> Vec<DiscardOption> {
+    let mut counts = count_tiles(hand);
+    let mut options = Vec::new();
+    let mut seen = [false; 34];
+    for &tile in hand {
+        let id = tile.id as usize;
+        if std::mem::replace(&mut seen[id], true) {
+            continue;
+        }
+        counts[id] -= 1;
+        let shanten = shanten(&counts, called_melds);
+        let mut ukeire = Vec::new();
+        for draw in 0..34 {
+            if counts[draw] == 4 {
+                continue;
+            }
+            counts[draw] += 1;
+            if self::shanten(&counts, called_melds) < shanten {
+                ukeire.extend(Tile::new(draw as u8).ok().map(|tile| Wait {
+                    tile,
+                    remaining: 4u8.saturating_sub(visible[draw]),
+                }));
+            }
+            counts[draw] -= 1;
+        }
+        counts[id] += 1;
+        options.push(DiscardOption {
+            tile,
+            shanten,
+            ukeire_count: ukeire.iter().map(|wait| wait.remaining as u32).sum(),
+            ukeire,
+        });
+    }
+    options.sort_by(|a, b| {
+        a.shanten
+            .cmp(&b.shanten)
+            .then(b.ukeire_count.cmp(&a.ukeire_count))
+            .then(a.tile.id.cmp(&b.tile.id))
+    });
+    options
+}
+
+fn is_orphan(id: usize) -> bool {
+    id >= 27 || matches!(id % 9, 0 | 8)
+}
+
+/// Try every way of splitting the tiles from `id` on into melds, partial melds
+/// (two tiles a meld short of one tile) and a pair, keeping the lowest shanten.
+/// The lowest remaining tile is always used or set aside first, so each split is tried once.
+fn search(counts: &mut [u8; 34], id: usize, blocks: i8, melds: i8, partials: i8, pair: bool, best: &mut i8) {
+    let Some(id) = (id..34).find(|&id| counts[id] > 0) else {
+        // Only `blocks` melds are needed, so extra partials do not help
+        let partials = partials.min(blocks - melds);
+        *best = (*best).min(2 * (blocks - melds) - partials - pair as i8);
+        return;
+    };
+    let suited = id < 27;
+    let room = melds + partials < blocks;
+
+    if counts[id] >= 3 {
+        counts[id] -= 3;
+        search(counts, id, blocks, melds + 1, partials, pair, best);
+        counts[id] += 3;
+    }
+    if suited && id % 9 <= 6 && counts[id + 1] > 0 && counts[id + 2] > 0 {
+        take(counts, &[id, id + 1, id + 2]);
+        search(counts, id, blocks, melds + 1, partials, pair, best);
+        give(counts, &[id, id + 1, id + 2]);
+    }
+    if counts[id] >= 2 {
+        counts[id] -= 2;
+        if !pair {
+            search(counts, id, blocks, melds, partials, true, best);
+        }
+        if room {
+            search(counts, id, blocks, melds, partials + 1, pair, best);
+        }
+        counts[id] += 2;
+    }
+    if room && suited {
+        for gap in [1, 2] {
+            if id % 9 + gap <= 8 && counts[id + gap] > 0 {
+                take(counts, &[id, id + gap]);
+                search(counts, id, blocks, melds, partials + 1, pair, best);
+                give(counts, &[id, id + gap]);
+            }
+        }
+    }
+
+    // Leave one copy unused
+    counts[id] -= 1;
+    search(counts, id, blocks, melds, partials, pair, best);
+    counts[id] += 1;
+}
+
+fn take(counts: &mut [u8; 34], ids: &[usize]) {
+    for &id in ids {
+        counts[id] -= 1;
+    }
+}
+
+fn give(counts: &mut [u8; 34], ids: &[usize]) {
+    for &id in ids {
+        counts[id] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_state::{MeldKind, Player};
+
+    fn counts(ids: &[u8]) -> [u8; 34] {
+        let mut counts = [0u8; 34];
+        for &id in ids {
+            counts[id as usize] += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_complete_and_tenpai_hands() {
+        // 123m 456m 123p 123s EE
+        let complete = counts(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 18, 19, 20, 27, 27]);
+        assert_eq!(shanten(&complete, 0), -1);
+        // 2345m waiting on 2m or 5m
+        let tenpai = counts(&[1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 27, 27, 27]);
+        assert_eq!(shanten(&tenpai, 0), 0);
+    }
+
+    #[test]
+    fn test_normal_shanten() {
+        // 13m 46m 9m 1p 5p 9p 1s 5s 9s E S: two partials, the rest isolated
+        let hand = counts(&[0, 2, 3, 5, 8, 9, 13, 17, 18, 22, 26, 27, 28]);
+        assert_eq!(normal_shanten(&hand, 0), 6);
+        // 123m 456m 12p 56p 99s: two melds, two partials and a pair
+        let hand = counts(&[0, 1, 2, 3, 4, 5, 9, 10, 13, 14, 26, 26, 33]);
+        assert_eq!(normal_shanten(&hand, 0), 1);
+        // Thirteen unrelated tiles
+        let hand = counts(&[0, 3, 6, 9, 12, 15, 18, 21, 24, 27, 28, 29, 30]);
+        assert_eq!(normal_shanten(&hand, 0), 8);
+    }
+
+    #[test]
+    fn test_seven_pairs_shanten() {
+        // Six pairs and a single
+        let hand = counts(&[0, 0, 4, 4, 8, 8, 12, 12, 16, 16, 27, 27, 33]);
+        assert_eq!(seven_pairs_shanten(&hand), 0);
+        assert_eq!(shanten(&hand, 0), 0);
+        // Four of a kind is only one pair, and a seventh kind is still missing
+        let hand = counts(&[0, 0, 0, 0, 4, 4, 8, 8, 12, 12, 16, 16, 27]);
+        assert_eq!(seven_pairs_shanten(&hand), 2);
+    }
+
+    #[test]
+    fn test_thirteen_orphans_shanten() {
+        let hand = counts(&[0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33]);
+        assert_eq!(thirteen_orphans_shanten(&hand), 0);
+        assert_eq!(shanten(&hand, 0), 0);
+        let hand = counts(&[0, 0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 4]);
+        assert_eq!(thirteen_orphans_shanten(&hand), 1);
+    }
+
+    #[test]
+    fn test_open_hands_only_count_normal_shapes() {
+        // Five pairs after calling a meld are no chiitoitsu
+        let hand = counts(&[0, 0, 4, 4, 8, 8, 12, 12, 16, 16]);
+        assert_eq!(shanten(&hand, 1), 2);
+        // Bare tanki wait after four calls
+        assert_eq!(shanten(&counts(&[27]), 4), 0);
+        assert_eq!(shanten(&counts(&[27, 27]), 4), -1);
+    }
+
+    #[test]
+    fn test_hand_shanten_counts_called_melds() {
+        let meld = Meld {
+            kind: MeldKind::Pon,
+            tiles: vec![Tile::new(31).unwrap(); 3],
+            called_tile: Tile::new(31).ok(),
+            from: Some(Player::Cpu1),
+        };
+        let hand: Vec<Tile> = [1, 2, 3, 4, 5, 6, 9, 10, 10, 10].iter().map(|&id| Tile::new(id).unwrap()).collect();
+        assert_eq!(hand_shanten(&hand, &[meld]), 0);
+    }
+
+    #[test]
+    fn test_agrees_with_win_checker() {
+        use crate::game::win_checker::{can_win, tenpai_waits};
+        use crate::models::tile::{create_wall, shuffle_wall, RedFives};
+        use rand::SeedableRng;
+
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..200 {
+            let mut wall = create_wall(RedFives::default());
+            shuffle_wall(&mut wall, &mut rng);
+            let hand = &wall[..13];
+            assert_eq!(hand_shanten(hand, &[]) == 0, !tenpai_waits(hand).is_empty(), "{:?}", hand);
+            for tile in &wall[13..20] {
+                let mut full = hand.to_vec();
+                full.push(*tile);
+                assert_eq!(hand_shanten(&full, &[]) == -1, can_win(&full), "{:?}", full);
+            }
+        }
+    }
+
+    #[test]
+    fn test_analyze_discards_ranks_by_ukeire() {
+        // 123m 456m 789m 12p EE + lone North: cutting North leaves a 12p wait on 3p
+        let hand: Vec<Tile> = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 27, 27, 30]
+            .iter()
+            .map(|&id| Tile::new(id).unwrap())
+            .collect();
+        let mut visible = count_tiles(&hand);
+        visible[11] += 1; // One 3p already discarded
+
+        let options = analyze_discards(&hand, 0, &visible);
+        assert_eq!(options.len(), 13);
+        let best = &options[0];
+        assert_eq!(best.tile, Tile::new(30).unwrap());
+        assert_eq!(best.shanten, 0);
+        assert_eq!(best.ukeire, vec![Wait { tile: Tile::new(11).unwrap(), remaining: 3 }]);
+        assert_eq!(best.ukeire_count, 3);
+        assert!(options.iter().all(|option| option.shanten >= 0));
+        assert!(options.windows(2).all(|pair| pair[0].shanten <= pair[1].shanten));
+    }
+}
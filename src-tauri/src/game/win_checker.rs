@@ -1,16 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::yaku::{evaluate, WinContext};
+use crate::models::game_state::Meld;
 use crate::models::tile::Tile;
 
-/// Check if a hand can win (4 melds + 1 pair)
-/// Hand must have exactly 14 tiles
+/// Check if a concealed hand can win (melds + 1 pair, seven pairs or thirteen orphans)
+/// Hand must have 14 tiles minus 3 for each called meld (14, 11, 8, 5 or 2)
 pub fn can_win(hand: &[Tile]) -> bool {
-    if hand.len() != 14 {
+    let Some(meld_count) = concealed_meld_count(hand.len()) else {
         return false;
-    }
+    };
 
-    // Count tiles by ID (array acts as a map: tile_id -> count)
-    let mut counts = [0u8; 34];
-    for tile in hand {
-        counts[tile.id as usize] += 1;
+    let counts = count_tiles(hand);
+    if hand.len() == 14 && (is_seven_pairs(&counts) || is_thirteen_orphans(&counts)) {
+        return true;
     }
 
     // Try each possible pair (head)
@@ -19,8 +22,8 @@ pub fn can_win(hand: &[Tile]) -> bool {
             let mut temp_counts = counts;
             temp_counts[pair_id] -= 2;
 
-            // Check if remaining 12 tiles can form 4 melds
-            if can_form_melds(&temp_counts, 4) {
+            // Check if remaining tiles can form the remaining melds
+            if can_form_melds(&temp_counts, meld_count) {
                 return true;
             }
         }
@@ -29,60 +32,220 @@ pub fn can_win(hand: &[Tile]) -> bool {
     false
 }
 
+/// Melds the concealed part of a complete hand must form, or None for an invalid size
+fn concealed_meld_count(tile_count: usize) -> Option<usize> {
+    if tile_count % 3 == 2 && tile_count <= 14 {
+        Some((tile_count - 2) / 3)
+    } else {
+        None
+    }
+}
+
+/// Seven distinct pairs (chiitoitsu); four of a kind does not count as two pairs
+pub fn is_seven_pairs(counts: &[u8; 34]) -> bool {
+    counts.iter().filter(|&&c| c == 2).count() == 7
+}
+
+/// One of each terminal and honor plus a pair of any of them (kokushi musou)
+pub fn is_thirteen_orphans(counts: &[u8; 34]) -> bool {
+    let mut has_pair = false;
+    for (id, &count) in counts.iter().enumerate() {
+        let is_orphan = id >= 27 || id % 9 == 0 || id % 9 == 8;
+        match (is_orphan, count) {
+            (true, 1) => {}
+            (true, 2) if !has_pair => has_pair = true,
+            (false, 0) => {}
+            _ => return false,
+        }
+    }
+    has_pair
+}
+
 /// Check if tiles can form the specified number of melds
 fn can_form_melds(counts: &[u8; 34], meld_count: usize) -> bool {
-    if meld_count == 0 {
-        // All tiles used
-        return counts.iter().all(|&c| c == 0);
+    let mut counts = *counts;
+    let mut meld_sets = Vec::new();
+    collect_meld_sets(&mut counts, meld_count, &mut Vec::new(), &mut meld_sets, true);
+    !meld_sets.is_empty()
+}
+
+/// Meld read from concealed tiles while decomposing a hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MeldShape {
+    /// Three consecutive suited tiles, identified by the lowest tile id
+    Sequence(u8),
+    /// Three identical tiles
+    Triplet(u8),
+}
+
+impl MeldShape {
+    /// Tile ids making up this meld
+    pub fn tile_ids(&self) -> [u8; 3] {
+        match *self {
+            MeldShape::Sequence(start) => [start, start + 1, start + 2],
+            MeldShape::Triplet(id) => [id, id, id],
+        }
     }
+}
 
-    // Try to form a triplet (same tile 3 times)
-    for id in 0..34 {
-        if counts[id] >= 3 {
-            let mut temp_counts = *counts;
-            temp_counts[id] -= 3;
-            if can_form_melds(&temp_counts, meld_count - 1) {
-                return true;
+/// One way of reading a winning hand as melds + pair
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Decomposition {
+    pub pair: u8,
+    pub melds: Vec<MeldShape>,
+}
+
+/// Count tiles by ID (array acts as a map: tile_id -> count)
+pub fn count_tiles(tiles: &[Tile]) -> [u8; 34] {
+    let mut counts = [0u8; 34];
+    for tile in tiles {
+        counts[tile.id as usize] += 1;
+    }
+    counts
+}
+
+/// List every distinct melds + pair reading of the concealed part of a winning hand
+/// Returns an empty list if the hand is not a winning shape
+pub fn decompose(hand: &[Tile]) -> Vec<Decomposition> {
+    let mut decompositions = Vec::new();
+    let Some(meld_count) = concealed_meld_count(hand.len()) else {
+        return decompositions;
+    };
+
+    let counts = count_tiles(hand);
+    for pair_id in 0..34 {
+        if counts[pair_id] >= 2 {
+            let mut temp_counts = counts;
+            temp_counts[pair_id] -= 2;
+
+            let mut meld_sets = Vec::new();
+            collect_meld_sets(&mut temp_counts, meld_count, &mut Vec::new(), &mut meld_sets, false);
+            for melds in meld_sets {
+                decompositions.push(Decomposition {
+                    pair: pair_id as u8,
+                    melds,
+                });
             }
         }
     }
 
-    // Try to form a sequence (consecutive tiles)
-    for suit_start in [0, 9, 18] {
-        // Manzu: 0-8, Pinzu: 9-17, Souzu: 18-26
-        for start in suit_start..=(suit_start + 6) {
-            // Check if we can form a sequence starting here
-            if counts[start] > 0 && counts[start + 1] > 0 && counts[start + 2] > 0 {
-                let mut temp_counts = *counts;
-                temp_counts[start] -= 1;
-                temp_counts[start + 1] -= 1;
-                temp_counts[start + 2] -= 1;
-                if can_form_melds(&temp_counts, meld_count - 1) {
-                    return true;
-                }
-            }
+    decompositions
+}
+
+/// Search meld sets that use up all counted tiles
+/// The lowest remaining tile must start a triplet or a sequence, so each set is found once.
+/// Stops after the first set when `first_only` is true.
+fn collect_meld_sets(
+    counts: &mut [u8; 34],
+    meld_count: usize,
+    current: &mut Vec<MeldShape>,
+    found: &mut Vec<Vec<MeldShape>>,
+    first_only: bool,
+) {
+    if first_only && !found.is_empty() {
+        return;
+    }
+
+    let Some(id) = counts.iter().position(|&c| c > 0) else {
+        // All tiles used
+        if meld_count == 0 {
+            found.push(current.clone());
         }
+        return;
+    };
+    if meld_count == 0 {
+        return;
     }
 
-    false
+    // Try to form a triplet (same tile 3 times)
+    if counts[id] >= 3 {
+        counts[id] -= 3;
+        current.push(MeldShape::Triplet(id as u8));
+        collect_meld_sets(counts, meld_count - 1, current, found, first_only);
+        current.pop();
+        counts[id] += 3;
+    }
+
+    // Try to form a sequence (consecutive tiles within one suit)
+    if id < 27 && id % 9 <= 6 && counts[id + 1] > 0 && counts[id + 2] > 0 {
+        counts[id] -= 1;
+        counts[id + 1] -= 1;
+        counts[id + 2] -= 1;
+        current.push(MeldShape::Sequence(id as u8));
+        collect_meld_sets(counts, meld_count - 1, current, found, first_only);
+        current.pop();
+        counts[id] += 1;
+        counts[id + 1] += 1;
+        counts[id + 2] += 1;
+    }
 }
 
 /// Check if player can win by claiming a specific discarded tile (ron)
-/// Hand must have exactly 13 tiles, and the specific discarded tile makes it 14 tiles that can win
-/// This checks if the player can ron the specific tile discarded by another player
-pub fn can_win_by_ron(hand: &[Tile], discarded_tile: Tile) -> bool {
-    if hand.len() != 13 {
+/// Concealed hand plus the discarded tile must complete the hand with at least one yaku;
+/// `melds` are the player's called melds and `ctx.win_tile` must be the discarded tile
+pub fn can_win_by_ron(hand: &[Tile], melds: &[Meld], discarded_tile: Tile, ctx: &WinContext) -> bool {
+    if hand.len() + melds.len() * 3 != 13 {
         return false;
     }
 
     let mut test_hand = hand.to_vec();
     test_hand.push(discarded_tile);
-    can_win(&test_hand)
+    can_win(&test_hand) && evaluate(&test_hand, melds, ctx).is_some()
+}
+
+/// Check if a hand plus its drawn tile wins by tsumo with at least one yaku
+/// `ctx.win_tile` must be the drawn tile
+pub fn can_win_by_tsumo(hand: &[Tile], melds: &[Meld], drawn_tile: Tile, ctx: &WinContext) -> bool {
+    if hand.len() + melds.len() * 3 != 13 {
+        return false;
+    }
+
+    let mut test_hand = hand.to_vec();
+    test_hand.push(drawn_tile);
+    can_win(&test_hand) && evaluate(&test_hand, melds, ctx).is_some()
+}
+
+/// Tile ids that complete a concealed hand of 13, 10, 7, 4 or 1 tiles, ignoring yaku
+/// A tile the hand already holds all four copies of cannot be waited on.
+pub fn tenpai_waits(hand: &[Tile]) -> Vec<u8> {
+    let counts = count_tiles(hand);
+    (0..34u8)
+        .filter(|&id| counts[id as usize] < 4)
+        .filter(|&id| {
+            let mut tiles = hand.to_vec();
+            tiles.extend(Tile::new(id));
+            can_win(&tiles)
+        })
+        .collect()
+}
+
+/// Every tile that completes a concealed hand of 13, 10, 7, 4 or 1 tiles, ignoring yaku
+pub fn waits(hand: &[Tile]) -> Vec<Tile> {
+    tenpai_waits(hand).into_iter().filter_map(|id| Tile::new(id).ok()).collect()
+}
+
+/// A winning tile and the copies of it the player has not seen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wait {
+    pub tile: Tile,
+    pub remaining: u8,
+}
+
+/// Waits of `hand` with the copies left after the `visible` tile counts
+pub fn waits_with_remaining(hand: &[Tile], visible: &[u8; 34]) -> Vec<Wait> {
+    waits(hand)
+        .into_iter()
+        .map(|tile| Wait {
+            tile,
+            remaining: 4u8.saturating_sub(visible[tile.id as usize]),
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::game_state::{MeldKind, Player, Wind};
 
     #[test]
     fn test_can_win_with_four_melds_and_pair() {
@@ -152,7 +315,30 @@ mod tests {
 
     #[test]
     fn test_can_win_by_ron() {
-        // 13 tiles that can win with discarded tile
+        // 13 tiles that can win with discarded tile (tanyao)
+        let hand = vec![
+            Tile::new(1).unwrap(), // 2 Man
+            Tile::new(2).unwrap(), // 3 Man
+            Tile::new(3).unwrap(), // 4 Man
+            Tile::new(4).unwrap(), // 5 Man
+            Tile::new(5).unwrap(), // 6 Man
+            Tile::new(6).unwrap(), // 7 Man
+            Tile::new(10).unwrap(), // 2 Pin
+            Tile::new(11).unwrap(), // 3 Pin
+            Tile::new(12).unwrap(), // 4 Pin
+            Tile::new(20).unwrap(), // 3 Sou
+            Tile::new(21).unwrap(), // 4 Sou
+            Tile::new(22).unwrap(), // 5 Sou
+            Tile::new(23).unwrap(), // 6 Sou
+        ];
+        let discarded = Tile::new(23).unwrap(); // 6 Sou
+        let ctx = WinContext::new(discarded, false, Wind::South, Wind::East);
+        assert!(can_win_by_ron(&hand, &[], discarded, &ctx));
+    }
+
+    #[test]
+    fn test_cannot_win_by_ron_without_yaku() {
+        // Complete shape but no yaku: 123m 456m 123p 789s + East pair for the South seat
         let hand = vec![
             Tile::new(0).unwrap(), // 1 Man
             Tile::new(1).unwrap(), // 2 Man
@@ -163,13 +349,48 @@ mod tests {
             Tile::new(9).unwrap(), // 1 Pin
             Tile::new(10).unwrap(), // 2 Pin
             Tile::new(11).unwrap(), // 3 Pin
-            Tile::new(18).unwrap(), // 1 Sou
-            Tile::new(19).unwrap(), // 2 Sou
-            Tile::new(20).unwrap(), // 3 Sou
+            Tile::new(24).unwrap(), // 7 Sou
+            Tile::new(25).unwrap(), // 8 Sou
+            Tile::new(26).unwrap(), // 9 Sou
             Tile::new(27).unwrap(), // East
         ];
         let discarded = Tile::new(27).unwrap(); // East
-        assert!(can_win_by_ron(&hand, discarded));
+        let ctx = WinContext::new(discarded, false, Wind::South, Wind::East);
+        assert!(!can_win_by_ron(&hand, &[], discarded, &ctx));
+
+        // The same hand by tsumo has menzen tsumo
+        let ctx = WinContext::new(discarded, true, Wind::South, Wind::East);
+        assert!(can_win_by_tsumo(&hand, &[], discarded, &ctx));
+    }
+
+    #[test]
+    fn test_decompose_lists_every_reading() {
+        // 111222333m + 456p + 77s reads as three triplets or three sequences
+        let hand = vec![
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(1).unwrap(), // 2 Man
+            Tile::new(1).unwrap(), // 2 Man
+            Tile::new(1).unwrap(), // 2 Man
+            Tile::new(2).unwrap(), // 3 Man
+            Tile::new(2).unwrap(), // 3 Man
+            Tile::new(2).unwrap(), // 3 Man
+            Tile::new(12).unwrap(), // 4 Pin
+            Tile::new(13).unwrap(), // 5 Pin
+            Tile::new(14).unwrap(), // 6 Pin
+            Tile::new(24).unwrap(), // 7 Sou
+            Tile::new(24).unwrap(), // 7 Sou
+        ];
+        let decompositions = decompose(&hand);
+        assert_eq!(decompositions.len(), 2);
+        assert!(decompositions.iter().all(|d| d.pair == 24));
+        assert!(decompositions
+            .iter()
+            .any(|d| d.melds.iter().filter(|m| matches!(m, MeldShape::Triplet(_))).count() == 3));
+        assert!(decompositions
+            .iter()
+            .any(|d| d.melds.iter().filter(|m| matches!(m, MeldShape::Sequence(_))).count() == 4));
     }
 
     #[test]
@@ -215,5 +436,165 @@ mod tests {
         ];
         assert!(can_win(&hand));
     }
-}
 
+    #[test]
+    fn test_can_win_with_seven_pairs() {
+        let hand = vec![
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(4).unwrap(), // 5 Man
+            Tile::new(4).unwrap(), // 5 Man
+            Tile::new(10).unwrap(), // 2 Pin
+            Tile::new(10).unwrap(), // 2 Pin
+            Tile::new(15).unwrap(), // 7 Pin
+            Tile::new(15).unwrap(), // 7 Pin
+            Tile::new(21).unwrap(), // 4 Sou
+            Tile::new(21).unwrap(), // 4 Sou
+            Tile::new(29).unwrap(), // West
+            Tile::new(29).unwrap(), // West
+            Tile::new(33).unwrap(), // Red
+            Tile::new(33).unwrap(), // Red
+        ];
+        assert!(can_win(&hand));
+    }
+
+    #[test]
+    fn test_seven_pairs_needs_distinct_pairs() {
+        // Four of a kind is not two pairs
+        let hand = vec![
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(10).unwrap(), // 2 Pin
+            Tile::new(10).unwrap(), // 2 Pin
+            Tile::new(15).unwrap(), // 7 Pin
+            Tile::new(15).unwrap(), // 7 Pin
+            Tile::new(21).unwrap(), // 4 Sou
+            Tile::new(21).unwrap(), // 4 Sou
+            Tile::new(29).unwrap(), // West
+            Tile::new(29).unwrap(), // West
+            Tile::new(33).unwrap(), // Red
+            Tile::new(33).unwrap(), // Red
+        ];
+        assert!(!can_win(&hand));
+    }
+
+    #[test]
+    fn test_can_win_with_thirteen_orphans() {
+        let orphans = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+        let mut hand: Vec<Tile> = orphans.iter().map(|&id| Tile::new(id).unwrap()).collect();
+        hand.push(Tile::new(31).unwrap()); // White pair
+        assert!(can_win(&hand));
+
+        // Thirteen orphans without a pair is not a win
+        let mut hand: Vec<Tile> = orphans.iter().map(|&id| Tile::new(id).unwrap()).collect();
+        hand.push(Tile::new(1).unwrap()); // 2 Man
+        assert!(!can_win(&hand));
+    }
+
+    #[test]
+    fn test_thirteen_orphans_thirteen_sided_wait() {
+        // One of each orphan is tenpai on all 13 orphans
+        let orphans = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+        let hand: Vec<Tile> = orphans.iter().map(|&id| Tile::new(id).unwrap()).collect();
+        for &id in &orphans {
+            let tile = Tile::new(id).unwrap();
+            let ctx = WinContext::new(tile, false, Wind::South, Wind::East);
+            assert!(can_win_by_ron(&hand, &[], tile, &ctx));
+        }
+        let tile = Tile::new(4).unwrap(); // 5 Man
+        let ctx = WinContext::new(tile, false, Wind::South, Wind::East);
+        assert!(!can_win_by_ron(&hand, &[], tile, &ctx));
+    }
+
+    #[test]
+    fn test_can_win_with_called_melds() {
+        // Two melds called: 8 concealed tiles must form 2 melds + pair
+        let hand = vec![
+            Tile::new(1).unwrap(), // 2 Man
+            Tile::new(2).unwrap(), // 3 Man
+            Tile::new(3).unwrap(), // 4 Man
+            Tile::new(13).unwrap(), // 5 Pin
+            Tile::new(13).unwrap(), // 5 Pin
+            Tile::new(13).unwrap(), // 5 Pin
+            Tile::new(22).unwrap(), // 5 Sou
+            Tile::new(22).unwrap(), // 5 Sou
+        ];
+        assert!(can_win(&hand));
+        assert!(!can_win(&hand[..7]));
+
+        // A single-tile wait after four calls
+        let hand = vec![Tile::new(31).unwrap(), Tile::new(31).unwrap()];
+        assert!(can_win(&hand));
+    }
+
+    #[test]
+    fn test_can_win_by_ron_with_called_meld() {
+        // Pon of White called; 10 concealed tiles wait on 5s
+        let melds = vec![Meld {
+            kind: MeldKind::Pon,
+            tiles: vec![Tile::new(31).unwrap(); 3],
+            called_tile: Some(Tile::new(31).unwrap()),
+            from: Some(Player::Cpu2),
+        }];
+        let hand = vec![
+            Tile::new(0).unwrap(), // 1 Man
+            Tile::new(1).unwrap(), // 2 Man
+            Tile::new(2).unwrap(), // 3 Man
+            Tile::new(12).unwrap(), // 4 Pin
+            Tile::new(13).unwrap(), // 5 Pin
+            Tile::new(14).unwrap(), // 6 Pin
+            Tile::new(24).unwrap(), // 7 Sou
+            Tile::new(25).unwrap(), // 8 Sou
+            Tile::new(26).unwrap(), // 9 Sou
+            Tile::new(22).unwrap(), // 5 Sou
+        ];
+        let discarded = Tile::new(22).unwrap(); // 5 Sou
+        let ctx = WinContext::new(discarded, false, Wind::South, Wind::East);
+        assert!(can_win_by_ron(&hand, &melds, discarded, &ctx));
+
+        // The concealed tiles alone are the wrong size for a closed hand
+        assert!(!can_win_by_ron(&hand, &[], discarded, &ctx));
+    }
+
+    #[test]
+    fn test_tenpai_waits() {
+        let tiles = |ids: &[u8]| -> Vec<Tile> { ids.iter().map(|&id| Tile::new(id).unwrap()).collect() };
+
+        // 1112345678999m: nine-sided wait
+        let hand = tiles(&[0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 8, 8]);
+        assert_eq!(tenpai_waits(&hand), (0..9).collect::<Vec<u8>>());
+
+        // 23m 456p 789s 111s East East: 1m-4m and no wait on the held triplets
+        let hand = tiles(&[1, 2, 12, 13, 14, 24, 25, 26, 18, 18, 18, 27, 27]);
+        assert_eq!(tenpai_waits(&hand), vec![0, 3]);
+
+        // Not tenpai
+        let hand = tiles(&[0, 4, 8, 9, 13, 17, 18, 22, 26, 27, 29, 31, 33]);
+        assert!(tenpai_waits(&hand).is_empty());
+
+        // Single tile after four calls
+        assert_eq!(tenpai_waits(&tiles(&[31])), vec![31]);
+    }
+
+    #[test]
+    fn test_waits_with_remaining() {
+        // 2345m 789m 123p EEE: waits on 2m and 5m
+        let hand: Vec<Tile> = [1, 2, 3, 4, 6, 7, 8, 9, 10, 11, 27, 27, 27]
+            .iter()
+            .map(|&id| Tile::new(id).unwrap())
+            .collect();
+        assert_eq!(waits(&hand), vec![Tile::new(1).unwrap(), Tile::new(4).unwrap()]);
+
+        let mut visible = count_tiles(&hand);
+        visible[4] += 2; // Two more 5m in the discards
+        assert_eq!(
+            waits_with_remaining(&hand, &visible),
+            vec![
+                Wait { tile: Tile::new(1).unwrap(), remaining: 3 },
+                Wait { tile: Tile::new(4).unwrap(), remaining: 1 },
+            ]
+        );
+    }
+}
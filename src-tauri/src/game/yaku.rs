@@ -0,0 +1,889 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::win_checker::{count_tiles, decompose, is_seven_pairs, is_thirteen_orphans, Decomposition, MeldShape};
+use crate::models::game_state::{Meld, Wind};
+use crate::models::tile::Tile;
+
+/// Situational facts about a win that cannot be read from the tiles alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WinContext {
+    /// Tile that completed the hand
+    pub win_tile: Tile,
+    pub is_tsumo: bool,
+    pub is_riichi: bool,
+    pub is_double_riichi: bool,
+    /// Riichi declared with the hand shown
+    pub is_open_riichi: bool,
+    pub is_ippatsu: bool,
+    /// Win on the last tile of the wall (haitei on tsumo, houtei on ron)
+    pub is_last_tile: bool,
+    /// Tsumo on the replacement tile drawn after a kan
+    pub is_rinshan: bool,
+    /// Ron on a tile added to a pon (robbing the kan)
+    pub is_chankan: bool,
+    /// Tsumo on the very first draw before any call (tenhou / chiihou)
+    pub is_first_draw: bool,
+    pub seat_wind: Wind,
+    pub round_wind: Wind,
+    /// Tanyao counts for open hands (kuitan rule)
+    pub kuitan: bool,
+}
+
+impl WinContext {
+    /// Context for a plain win with no situational yaku
+    pub fn new(win_tile: Tile, is_tsumo: bool, seat_wind: Wind, round_wind: Wind) -> Self {
+        WinContext {
+            win_tile,
+            is_tsumo,
+            is_riichi: false,
+            is_double_riichi: false,
+            is_open_riichi: false,
+            is_ippatsu: false,
+            is_last_tile: false,
+            is_rinshan: false,
+            is_chankan: false,
+            is_first_draw: false,
+            seat_wind,
+            round_wind,
+            kuitan: true,
+        }
+    }
+}
+
+/// Standard riichi yaku
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Yaku {
+    // 1 han
+    Riichi,
+    Ippatsu,
+    MenzenTsumo,
+    Pinfu,
+    Tanyao,
+    Iipeikou,
+    Haku,
+    Hatsu,
+    Chun,
+    SeatWind,
+    RoundWind,
+    Haitei,
+    Houtei,
+    Rinshan,
+    Chankan,
+    /// Extra han for an open riichi, on top of riichi
+    OpenRiichi,
+    // 2 han
+    DoubleRiichi,
+    Sanshoku,
+    Ittsu,
+    Chanta,
+    Toitoi,
+    Sanankou,
+    SanshokuDoukou,
+    Shousangen,
+    Honroutou,
+    Chiitoitsu,
+    Sankantsu,
+    // 3 han and more
+    Junchan,
+    Ryanpeikou,
+    Honitsu,
+    Chinitsu,
+    // Yakuman
+    KokushiMusou,
+    Suuankou,
+    Daisangen,
+    Shousuushii,
+    Daisuushii,
+    Tsuuiisou,
+    Chinroutou,
+    Ryuuiisou,
+    ChuurenPoutou,
+    Tenhou,
+    Chiihou,
+    Suukantsu,
+}
+
+impl Yaku {
+    /// Han value; `closed` selects the concealed value for yaku that lose a han when open
+    /// Yakuman count as 13 so they always outrank regular hands
+    pub fn han(&self, closed: bool) -> u8 {
+        let open_penalty = if closed { 0 } else { 1 };
+        match self {
+            Yaku::Riichi
+            | Yaku::Ippatsu
+            | Yaku::MenzenTsumo
+            | Yaku::Pinfu
+            | Yaku::Tanyao
+            | Yaku::Iipeikou
+            | Yaku::Haku
+            | Yaku::Hatsu
+            | Yaku::Chun
+            | Yaku::SeatWind
+            | Yaku::RoundWind
+            | Yaku::Haitei
+            | Yaku::Houtei
+            | Yaku::Rinshan
+            | Yaku::Chankan
+            | Yaku::OpenRiichi => 1,
+            Yaku::DoubleRiichi
+            | Yaku::Toitoi
+            | Yaku::Sanankou
+            | Yaku::SanshokuDoukou
+            | Yaku::Shousangen
+            | Yaku::Honroutou
+            | Yaku::Chiitoitsu
+            | Yaku::Sankantsu => 2,
+            Yaku::Sanshoku | Yaku::Ittsu | Yaku::Chanta => 2 - open_penalty,
+            Yaku::Junchan | Yaku::Honitsu => 3 - open_penalty,
+            Yaku::Ryanpeikou => 3,
+            Yaku::Chinitsu => 6 - open_penalty,
+            _ => 13,
+        }
+    }
+
+    pub fn is_yakuman(&self) -> bool {
+        matches!(
+            self,
+            Yaku::KokushiMusou
+                | Yaku::Suuankou
+                | Yaku::Daisangen
+                | Yaku::Shousuushii
+                | Yaku::Daisuushii
+                | Yaku::Tsuuiisou
+                | Yaku::Chinroutou
+                | Yaku::Ryuuiisou
+                | Yaku::ChuurenPoutou
+                | Yaku::Tenhou
+                | Yaku::Chiihou
+                | Yaku::Suukantsu
+        )
+    }
+}
+
+/// Shape of the wait that the winning tile completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitShape {
+    Ryanmen, // Open two-sided sequence wait (e.g. 23 waiting 1/4)
+    Kanchan, // Closed middle wait (e.g. 13 waiting 2)
+    Penchan, // Edge wait (12 waiting 3, 89 waiting 7)
+    Shanpon, // Two pairs, one becomes a triplet
+    Tanki,   // Single tile waiting for its pair
+}
+
+/// Overall shape of a winning hand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandShape {
+    Standard,        // 4 melds + 1 pair
+    SevenPairs,      // Chiitoitsu
+    ThirteenOrphans, // Kokushi musou
+}
+
+/// Yaku found for one reading of a winning hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct YakuResult {
+    pub yaku: Vec<Yaku>,
+    /// Total han of the listed yaku (13 per yakuman)
+    pub han: u8,
+    /// Number of yakuman (0 for a regular hand)
+    pub yakuman: u8,
+    pub shape: HandShape,
+    /// No melds were called (closed kans keep the hand closed)
+    pub closed: bool,
+    /// Melds + pair of the concealed tiles for a standard shape (None for seven pairs and thirteen orphans)
+    pub decomposition: Option<Decomposition>,
+    pub wait: WaitShape,
+    /// Index into `decomposition.melds` of the meld completed by the winning tile (None for tanki)
+    pub win_meld: Option<usize>,
+}
+
+/// Evaluate the highest-valued reading of a winning hand
+/// `hand` is the concealed tiles including the winning tile, `melds` the called melds.
+/// Returns None if the hand is not a winning shape or has no yaku
+pub fn evaluate(hand: &[Tile], melds: &[Meld], ctx: &WinContext) -> Option<YakuResult> {
+    evaluate_all(hand, melds, ctx)
+        .into_iter()
+        .max_by_key(|result| (result.yakuman, result.han))
+}
+
+/// Evaluate every reading (decomposition + wait) of a winning hand that has at least one yaku
+pub fn evaluate_all(hand: &[Tile], melds: &[Meld], ctx: &WinContext) -> Vec<YakuResult> {
+    let mut all_tiles = hand.to_vec();
+    all_tiles.extend(melds.iter().flat_map(|meld| meld.tiles.iter().copied()));
+    let counts = count_tiles(&all_tiles);
+    let win_id = ctx.win_tile.id;
+    let mut results = Vec::new();
+
+    let decompositions = decompose(hand);
+    for decomposition in &decompositions {
+        for (wait, win_meld) in readings(decomposition, win_id) {
+            let reading = Reading {
+                counts: &counts,
+                shape: HandShape::Standard,
+                decomposition: Some(decomposition),
+                called: melds,
+                wait,
+                win_meld,
+                ctx,
+            };
+            results.extend(reading.result());
+        }
+    }
+
+    // Special shapes are always a single wait on the last tile
+    if hand.len() == 14 {
+        for shape in [HandShape::SevenPairs, HandShape::ThirteenOrphans] {
+            let matches = match shape {
+                HandShape::SevenPairs => is_seven_pairs(&counts),
+                _ => is_thirteen_orphans(&counts),
+            };
+            if matches {
+                let reading = Reading {
+                    counts: &counts,
+                    shape,
+                    decomposition: None,
+                    called: melds,
+                    wait: WaitShape::Tanki,
+                    win_meld: None,
+                    ctx,
+                };
+                results.extend(reading.result());
+            }
+        }
+    }
+
+    results
+}
+
+/// Every place the winning tile can sit in a decomposition, with the resulting wait
+fn readings(decomposition: &Decomposition, win_id: u8) -> Vec<(WaitShape, Option<usize>)> {
+    let mut readings = Vec::new();
+
+    if decomposition.pair == win_id {
+        readings.push((WaitShape::Tanki, None));
+    }
+    for (index, meld) in decomposition.melds.iter().enumerate() {
+        match *meld {
+            MeldShape::Triplet(id) if id == win_id => {
+                readings.push((WaitShape::Shanpon, Some(index)));
+            }
+            MeldShape::Sequence(start) if (start..start + 3).contains(&win_id) => {
+                let number = start % 9 + 1;
+                let wait = if win_id == start + 1 {
+                    WaitShape::Kanchan
+                } else if (win_id == start && number == 7) || (win_id == start + 2 && number == 1) {
+                    WaitShape::Penchan
+                } else {
+                    WaitShape::Ryanmen
+                };
+                readings.push((wait, Some(index)));
+            }
+            _ => {}
+        }
+    }
+
+    readings
+}
+
+fn is_honor(id: u8) -> bool {
+    id >= 27
+}
+
+fn is_terminal(id: u8) -> bool {
+    id < 27 && matches!(id % 9, 0 | 8)
+}
+
+fn is_dragon(id: u8) -> bool {
+    (31..=33).contains(&id)
+}
+
+fn is_wind(id: u8) -> bool {
+    (27..=30).contains(&id)
+}
+
+/// One reading of a winning hand being checked for yaku
+struct Reading<'a> {
+    counts: &'a [u8; 34],
+    shape: HandShape,
+    /// Melds + pair of the concealed tiles (None for seven pairs and thirteen orphans)
+    decomposition: Option<&'a Decomposition>,
+    /// Called melds
+    called: &'a [Meld],
+    wait: WaitShape,
+    win_meld: Option<usize>,
+    ctx: &'a WinContext,
+}
+
+impl Reading<'_> {
+    /// Yaku result for this reading (None if it has no yaku)
+    fn result(&self) -> Option<YakuResult> {
+        let yaku = self.yaku();
+        if yaku.is_empty() {
+            return None;
+        }
+
+        let closed = self.is_closed();
+        let han = yaku.iter().map(|y| y.han(closed)).sum();
+        let yakuman = yaku.iter().filter(|y| y.is_yakuman()).count() as u8;
+        Some(YakuResult {
+            yaku,
+            han,
+            yakuman,
+            shape: self.shape,
+            closed,
+            decomposition: self.decomposition.cloned(),
+            wait: self.wait,
+            win_meld: self.win_meld,
+        })
+    }
+
+    fn tile_ids(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..34u8).filter(|&id| self.counts[id as usize] > 0)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.called.iter().all(Meld::is_concealed)
+    }
+
+    /// Concealed melds followed by called melds
+    fn melds(&self) -> Vec<MeldShape> {
+        let concealed = self.decomposition.map_or(&[][..], |d| d.melds.as_slice());
+        concealed
+            .iter()
+            .copied()
+            .chain(self.called.iter().map(Meld::shape))
+            .collect()
+    }
+
+    fn sequences(&self) -> Vec<u8> {
+        self.melds()
+            .iter()
+            .filter_map(|meld| match meld {
+                MeldShape::Sequence(start) => Some(*start),
+                MeldShape::Triplet(_) => None,
+            })
+            .collect()
+    }
+
+    fn triplets(&self) -> Vec<u8> {
+        self.melds()
+            .iter()
+            .filter_map(|meld| match meld {
+                MeldShape::Triplet(id) => Some(*id),
+                MeldShape::Sequence(_) => None,
+            })
+            .collect()
+    }
+
+    /// Triplets formed entirely from drawn tiles (a triplet completed by ron counts as open)
+    fn concealed_triplet_count(&self) -> usize {
+        let Some(decomposition) = self.decomposition else {
+            return 0;
+        };
+        let concealed = decomposition
+            .melds
+            .iter()
+            .enumerate()
+            .filter(|(index, meld)| {
+                let completed_by_ron = !self.ctx.is_tsumo && self.win_meld == Some(*index);
+                matches!(meld, MeldShape::Triplet(_)) && !completed_by_ron
+            })
+            .count();
+        let called = self
+            .called
+            .iter()
+            .filter(|meld| meld.is_concealed() && matches!(meld.shape(), MeldShape::Triplet(_)))
+            .count();
+        concealed + called
+    }
+
+    fn kan_count(&self) -> usize {
+        self.called.iter().filter(|meld| meld.is_kan()).count()
+    }
+
+    fn is_value_tile(&self, id: u8) -> bool {
+        is_dragon(id) || id == self.ctx.seat_wind.tile_id() || id == self.ctx.round_wind.tile_id()
+    }
+
+    /// Every meld and the pair contain a terminal or honor
+    fn all_groups_have_terminal_or_honor(&self, decomposition: &Decomposition) -> bool {
+        let pair = decomposition.pair;
+        (is_terminal(pair) || is_honor(pair))
+            && self
+                .melds()
+                .iter()
+                .all(|meld| meld.tile_ids().iter().any(|&id| is_terminal(id) || is_honor(id)))
+    }
+
+    /// Suits present among suited tiles (0 = Manzu, 1 = Pinzu, 2 = Souzu)
+    fn suits(&self) -> Vec<u8> {
+        let mut suits: Vec<u8> = self.tile_ids().filter(|&id| !is_honor(id)).map(|id| id / 9).collect();
+        suits.dedup();
+        suits
+    }
+
+    fn yaku(&self) -> Vec<Yaku> {
+        let yakuman = self.yakuman();
+        if !yakuman.is_empty() {
+            return yakuman;
+        }
+
+        let ctx = self.ctx;
+        let mut yaku = Vec::new();
+        let has_honor = self.tile_ids().any(is_honor);
+
+        if ctx.is_double_riichi {
+            yaku.push(Yaku::DoubleRiichi);
+        } else if ctx.is_riichi {
+            yaku.push(Yaku::Riichi);
+        }
+        if ctx.is_open_riichi && (ctx.is_riichi || ctx.is_double_riichi) {
+            yaku.push(Yaku::OpenRiichi);
+        }
+        if ctx.is_ippatsu && (ctx.is_riichi || ctx.is_double_riichi) {
+            yaku.push(Yaku::Ippatsu);
+        }
+        if ctx.is_tsumo && self.is_closed() {
+            yaku.push(Yaku::MenzenTsumo);
+        }
+        if ctx.is_last_tile {
+            yaku.push(if ctx.is_tsumo { Yaku::Haitei } else { Yaku::Houtei });
+        }
+        if ctx.is_rinshan && ctx.is_tsumo {
+            yaku.push(Yaku::Rinshan);
+        }
+        if ctx.is_chankan && !ctx.is_tsumo {
+            yaku.push(Yaku::Chankan);
+        }
+
+        if self.shape == HandShape::SevenPairs {
+            yaku.push(Yaku::Chiitoitsu);
+        }
+        if let Some(decomposition) = self.decomposition {
+            self.push_meld_yaku(decomposition, &mut yaku);
+        }
+
+        if (ctx.kuitan || self.is_closed()) && self.tile_ids().all(|id| !is_honor(id) && !is_terminal(id)) {
+            yaku.push(Yaku::Tanyao);
+        }
+        if self.tile_ids().all(|id| is_terminal(id) || is_honor(id)) {
+            yaku.push(Yaku::Honroutou);
+        } else if let Some(decomposition) = self.decomposition {
+            if !self.sequences().is_empty() && self.all_groups_have_terminal_or_honor(decomposition) {
+                yaku.push(if has_honor { Yaku::Chanta } else { Yaku::Junchan });
+            }
+        }
+        if self.suits().len() == 1 {
+            yaku.push(if has_honor { Yaku::Honitsu } else { Yaku::Chinitsu });
+        }
+
+        yaku
+    }
+
+    /// Yaku that depend on the melds + pair reading
+    fn push_meld_yaku(&self, decomposition: &Decomposition, yaku: &mut Vec<Yaku>) {
+        let ctx = self.ctx;
+        let sequences = self.sequences();
+        let triplets = self.triplets();
+
+        let closed = self.is_closed();
+        if closed
+            && sequences.len() == 4
+            && !self.is_value_tile(decomposition.pair)
+            && self.wait == WaitShape::Ryanmen
+        {
+            yaku.push(Yaku::Pinfu);
+        }
+
+        // Identical sequence pairs: one pair is iipeikou, two pairs is ryanpeikou (closed only)
+        let mut sorted_sequences = if closed { sequences.clone() } else { Vec::new() };
+        sorted_sequences.sort();
+        let mut identical_pairs = 0;
+        let mut i = 0;
+        while i + 1 < sorted_sequences.len() {
+            if sorted_sequences[i] == sorted_sequences[i + 1] {
+                identical_pairs += 1;
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+        match identical_pairs {
+            2 => yaku.push(Yaku::Ryanpeikou),
+            1 => yaku.push(Yaku::Iipeikou),
+            _ => {}
+        }
+
+        for &id in &triplets {
+            match id {
+                31 => yaku.push(Yaku::Haku),
+                32 => yaku.push(Yaku::Hatsu),
+                33 => yaku.push(Yaku::Chun),
+                _ => {}
+            }
+            if id == ctx.seat_wind.tile_id() {
+                yaku.push(Yaku::SeatWind);
+            }
+            if id == ctx.round_wind.tile_id() {
+                yaku.push(Yaku::RoundWind);
+            }
+        }
+
+        if (0..7).any(|n| [n, n + 9, n + 18].iter().all(|start| sequences.contains(start))) {
+            yaku.push(Yaku::Sanshoku);
+        }
+        if [0, 9, 18].iter().any(|&base| [base, base + 3, base + 6].iter().all(|start| sequences.contains(start))) {
+            yaku.push(Yaku::Ittsu);
+        }
+        if (0..9).any(|n| [n, n + 9, n + 18].iter().all(|id| triplets.contains(id))) {
+            yaku.push(Yaku::SanshokuDoukou);
+        }
+
+        if triplets.len() == 4 {
+            yaku.push(Yaku::Toitoi);
+        }
+        if self.concealed_triplet_count() == 3 {
+            yaku.push(Yaku::Sanankou);
+        }
+        if triplets.iter().filter(|&&id| is_dragon(id)).count() == 2 && is_dragon(decomposition.pair) {
+            yaku.push(Yaku::Shousangen);
+        }
+        if self.kan_count() == 3 {
+            yaku.push(Yaku::Sankantsu);
+        }
+    }
+
+    fn yakuman(&self) -> Vec<Yaku> {
+        let ctx = self.ctx;
+        let mut yakuman = Vec::new();
+        let triplets = self.triplets();
+
+        if ctx.is_first_draw && ctx.is_tsumo {
+            yakuman.push(if ctx.seat_wind == Wind::East { Yaku::Tenhou } else { Yaku::Chiihou });
+        }
+        if self.shape == HandShape::ThirteenOrphans {
+            yakuman.push(Yaku::KokushiMusou);
+            return yakuman;
+        }
+        if let Some(decomposition) = self.decomposition {
+            if self.concealed_triplet_count() == 4 {
+                yakuman.push(Yaku::Suuankou);
+            }
+            if triplets.iter().filter(|&&id| is_dragon(id)).count() == 3 {
+                yakuman.push(Yaku::Daisangen);
+            }
+            let wind_triplets = triplets.iter().filter(|&&id| is_wind(id)).count();
+            if wind_triplets == 4 {
+                yakuman.push(Yaku::Daisuushii);
+            } else if wind_triplets == 3 && is_wind(decomposition.pair) {
+                yakuman.push(Yaku::Shousuushii);
+            }
+            if self.is_chuuren() {
+                yakuman.push(Yaku::ChuurenPoutou);
+            }
+            if self.kan_count() == 4 {
+                yakuman.push(Yaku::Suukantsu);
+            }
+        }
+        if self.tile_ids().all(is_honor) {
+            yakuman.push(Yaku::Tsuuiisou);
+        }
+        if self.tile_ids().all(is_terminal) {
+            yakuman.push(Yaku::Chinroutou);
+        }
+        if self.tile_ids().all(|id| matches!(id, 19 | 20 | 21 | 23 | 25 | 32)) {
+            yakuman.push(Yaku::Ryuuiisou);
+        }
+
+        yakuman
+    }
+
+    /// 1112345678999 in one suit plus any tile of that suit, closed
+    fn is_chuuren(&self) -> bool {
+        let suits = self.suits();
+        if !self.called.is_empty() || suits.len() != 1 || self.tile_ids().any(is_honor) {
+            return false;
+        }
+        let base = suits[0] as usize * 9;
+        let required = [3, 1, 1, 1, 1, 1, 1, 1, 3];
+        (0..9).all(|n| self.counts[base + n] >= required[n])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game_state::{MeldKind, Player};
+
+    /// Build tiles from ids
+    fn tiles(ids: &[u8]) -> Vec<Tile> {
+        ids.iter().map(|&id| Tile::new(id).unwrap()).collect()
+    }
+
+    fn ron(win_id: u8) -> WinContext {
+        WinContext::new(Tile::new(win_id).unwrap(), false, Wind::South, Wind::East)
+    }
+
+    #[test]
+    fn test_no_yaku_for_open_wait_without_value() {
+        // 123m 456m 123p 789s + 99m, tanki on 9m with no yaku
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 8, 8]);
+        assert!(evaluate(&hand, &[], &ron(8)).is_none());
+    }
+
+    #[test]
+    fn test_pinfu_tanyao() {
+        // 234m 567m 234p 345s + 66s, ron 4p completing 23p ryanmen
+        let hand = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let result = evaluate(&hand, &[], &ron(12)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Pinfu));
+        assert!(result.yaku.contains(&Yaku::Tanyao));
+        assert_eq!(result.wait, WaitShape::Ryanmen);
+        assert_eq!(result.han, 2);
+    }
+
+    #[test]
+    fn test_kanchan_is_not_pinfu() {
+        // Same hand won on 3p read as a 2-4p kanchan
+        let hand = tiles(&[1, 2, 3, 4, 5, 6, 10, 11, 12, 20, 21, 22, 23, 23]);
+        let result = evaluate(&hand, &[], &ron(11)).unwrap();
+        assert!(!result.yaku.contains(&Yaku::Pinfu));
+        assert_eq!(result.wait, WaitShape::Kanchan);
+    }
+
+    #[test]
+    fn test_menzen_tsumo() {
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 8, 8]);
+        let ctx = WinContext::new(Tile::new(8).unwrap(), true, Wind::South, Wind::East);
+        let result = evaluate(&hand, &[], &ctx).unwrap();
+        assert_eq!(result.yaku, vec![Yaku::MenzenTsumo]);
+    }
+
+    #[test]
+    fn test_yakuhai_seat_and_round_wind() {
+        // East triplet for the East seat in the East round counts twice
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 27, 27, 27, 18, 18]);
+        let ctx = WinContext::new(Tile::new(0).unwrap(), false, Wind::East, Wind::East);
+        let result = evaluate(&hand, &[], &ctx).unwrap();
+        assert!(result.yaku.contains(&Yaku::SeatWind));
+        assert!(result.yaku.contains(&Yaku::RoundWind));
+        assert_eq!(result.han, 2);
+    }
+
+    #[test]
+    fn test_iipeikou_and_ryanpeikou() {
+        // 112233m 456p 789s 55p
+        let hand = tiles(&[0, 0, 1, 1, 2, 2, 12, 13, 14, 24, 25, 26, 13, 13]);
+        let result = evaluate(&hand, &[], &ron(26)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Iipeikou));
+
+        // 112233m 445566p 99s
+        let hand = tiles(&[0, 0, 1, 1, 2, 2, 12, 12, 13, 13, 14, 14, 26, 26]);
+        let result = evaluate(&hand, &[], &ron(26)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Ryanpeikou));
+        assert!(!result.yaku.contains(&Yaku::Iipeikou));
+    }
+
+    #[test]
+    fn test_sanshoku_and_ittsu() {
+        // 123m 123p 123s 789m + 55s
+        let hand = tiles(&[0, 1, 2, 9, 10, 11, 18, 19, 20, 6, 7, 8, 22, 22]);
+        let result = evaluate(&hand, &[], &ron(22)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Sanshoku));
+
+        // 123m 456m 789m 123p + 55s
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 22, 22]);
+        let result = evaluate(&hand, &[], &ron(22)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Ittsu));
+    }
+
+    #[test]
+    fn test_chanta_and_junchan() {
+        // 123m 789p 123s 999s + West pair (not a value wind for the South seat in the East round)
+        let hand = tiles(&[0, 1, 2, 15, 16, 17, 18, 19, 20, 26, 26, 26, 29, 29]);
+        let result = evaluate(&hand, &[], &ron(0)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Chanta));
+
+        // 123m 789p 123s 999s + 11m
+        let hand = tiles(&[0, 1, 2, 15, 16, 17, 18, 19, 20, 26, 26, 26, 0, 0]);
+        let result = evaluate(&hand, &[], &ron(1)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Junchan));
+        assert!(!result.yaku.contains(&Yaku::Chanta));
+    }
+
+    #[test]
+    fn test_toitoi_and_sanankou() {
+        // 111m 999p 555s 777s + 22m, ron on 7s makes that triplet open
+        let hand = tiles(&[0, 0, 0, 17, 17, 17, 22, 22, 22, 24, 24, 24, 1, 1]);
+        let result = evaluate(&hand, &[], &ron(24)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Toitoi));
+        assert!(result.yaku.contains(&Yaku::Sanankou));
+        assert!(!result.yaku.contains(&Yaku::Suuankou));
+    }
+
+    #[test]
+    fn test_honitsu_and_chinitsu() {
+        // 123m 456m 789m 234m + 55m
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4, 4]);
+        let result = evaluate(&hand, &[], &ron(8)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Chinitsu));
+        assert!(!result.yaku.contains(&Yaku::Honitsu));
+
+        // 123m 456m 789m + WWW + 55m
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 29, 29, 29, 4, 4]);
+        let result = evaluate(&hand, &[], &ron(8)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Honitsu));
+    }
+
+    #[test]
+    fn test_shousangen() {
+        // White + Green triplets, Red pair, 123m 456p
+        let hand = tiles(&[31, 31, 31, 32, 32, 32, 33, 33, 0, 1, 2, 12, 13, 14]);
+        let result = evaluate(&hand, &[], &ron(0)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Shousangen));
+        assert!(result.yaku.contains(&Yaku::Haku));
+        assert!(result.yaku.contains(&Yaku::Hatsu));
+    }
+
+    #[test]
+    fn test_yakuman_replaces_regular_yaku() {
+        // Daisangen: three dragon triplets + 123m + 99p
+        let hand = tiles(&[31, 31, 31, 32, 32, 32, 33, 33, 33, 0, 1, 2, 17, 17]);
+        let result = evaluate(&hand, &[], &ron(0)).unwrap();
+        assert_eq!(result.yaku, vec![Yaku::Daisangen]);
+        assert_eq!(result.yakuman, 1);
+
+        // Chuuren poutou: 1112345678999m + 5m
+        let hand = tiles(&[0, 0, 0, 1, 2, 3, 4, 4, 5, 6, 7, 8, 8, 8]);
+        let result = evaluate(&hand, &[], &ron(4)).unwrap();
+        assert!(result.yaku.contains(&Yaku::ChuurenPoutou));
+    }
+
+    #[test]
+    fn test_suuankou_on_tsumo() {
+        let hand = tiles(&[0, 0, 0, 17, 17, 17, 22, 22, 22, 24, 24, 24, 1, 1]);
+        let ctx = WinContext::new(Tile::new(24).unwrap(), true, Wind::South, Wind::East);
+        let result = evaluate(&hand, &[], &ctx).unwrap();
+        assert_eq!(result.yaku, vec![Yaku::Suuankou]);
+    }
+
+    #[test]
+    fn test_riichi_situational_yaku() {
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 8, 8]);
+        let mut ctx = ron(8);
+        ctx.is_riichi = true;
+        ctx.is_ippatsu = true;
+        ctx.is_last_tile = true;
+        let result = evaluate(&hand, &[], &ctx).unwrap();
+        assert_eq!(result.yaku, vec![Yaku::Riichi, Yaku::Ippatsu, Yaku::Houtei]);
+        assert_eq!(result.han, 3);
+    }
+
+    #[test]
+    fn test_open_riichi_adds_a_han() {
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 9, 10, 11, 24, 25, 26, 8, 8]);
+        let mut ctx = ron(8);
+        ctx.is_riichi = true;
+        ctx.is_open_riichi = true;
+        let result = evaluate(&hand, &[], &ctx).unwrap();
+        assert_eq!(result.yaku, vec![Yaku::Riichi, Yaku::OpenRiichi]);
+        assert_eq!(result.han, 2);
+    }
+
+    #[test]
+    fn test_chiitoitsu() {
+        // 11m 55m 22p 77p 44s WW RR
+        let hand = tiles(&[0, 0, 4, 4, 10, 10, 15, 15, 21, 21, 29, 29, 33, 33]);
+        let result = evaluate(&hand, &[], &ron(33)).unwrap();
+        assert_eq!(result.shape, HandShape::SevenPairs);
+        assert_eq!(result.yaku, vec![Yaku::Chiitoitsu]);
+        assert_eq!(result.wait, WaitShape::Tanki);
+        assert!(result.decomposition.is_none());
+    }
+
+    #[test]
+    fn test_ryanpeikou_beats_chiitoitsu() {
+        // 112233m 445566p 99s reads as both; ryanpeikou is worth more
+        let hand = tiles(&[0, 0, 1, 1, 2, 2, 12, 12, 13, 13, 14, 14, 26, 26]);
+        let result = evaluate(&hand, &[], &ron(26)).unwrap();
+        assert_eq!(result.shape, HandShape::Standard);
+        assert!(result.yaku.contains(&Yaku::Ryanpeikou));
+    }
+
+    #[test]
+    fn test_kokushi_musou() {
+        let hand = tiles(&[0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33, 0]);
+        let result = evaluate(&hand, &[], &ron(0)).unwrap();
+        assert_eq!(result.shape, HandShape::ThirteenOrphans);
+        assert_eq!(result.yaku, vec![Yaku::KokushiMusou]);
+        assert_eq!(result.yakuman, 1);
+    }
+
+    fn pon(id: u8) -> Meld {
+        Meld {
+            kind: MeldKind::Pon,
+            tiles: tiles(&[id, id, id]),
+            called_tile: Some(Tile::new(id).unwrap()),
+            from: Some(Player::Cpu1),
+        }
+    }
+
+    #[test]
+    fn test_open_hand_yaku() {
+        // Pon of Red + 123m 456m 789m + 55m: chun, ittsu (open) and honitsu (open)
+        let melds = vec![pon(33)];
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 6, 7, 8, 4, 4]);
+        let result = evaluate(&hand, &melds, &ron(8)).unwrap();
+        assert!(!result.closed);
+        assert!(result.yaku.contains(&Yaku::Chun));
+        assert!(result.yaku.contains(&Yaku::Ittsu));
+        assert!(result.yaku.contains(&Yaku::Honitsu));
+        assert_eq!(result.han, 1 + 1 + 2);
+    }
+
+    #[test]
+    fn test_open_hand_without_yaku() {
+        // Pon of 9s + 123m 456m 234p + 55s: nothing, and no menzen tsumo for an open hand
+        let melds = vec![pon(26)];
+        let hand = tiles(&[0, 1, 2, 3, 4, 5, 10, 11, 12, 22, 22]);
+        assert!(evaluate(&hand, &melds, &ron(22)).is_none());
+        let ctx = WinContext::new(Tile::new(22).unwrap(), true, Wind::South, Wind::East);
+        assert!(evaluate(&hand, &melds, &ctx).is_none());
+    }
+
+    #[test]
+    fn test_open_toitoi_counts_called_triplets() {
+        // Pon of 2m and 8p + concealed 444s 666s + 77s: toitoi, sanankou on tsumo, tanyao
+        let melds = vec![pon(1), pon(16)];
+        let hand = tiles(&[21, 21, 21, 23, 23, 23, 24, 24]);
+        let ctx = WinContext::new(Tile::new(24).unwrap(), true, Wind::South, Wind::East);
+        let result = evaluate(&hand, &melds, &ctx).unwrap();
+        assert!(result.yaku.contains(&Yaku::Toitoi));
+        assert!(result.yaku.contains(&Yaku::Tanyao));
+        assert!(!result.yaku.contains(&Yaku::Sanankou));
+    }
+
+    #[test]
+    fn test_open_tanyao_needs_kuitan() {
+        // Pon of 2m + 345m 456p 678s + 55p
+        let melds = vec![pon(1)];
+        let hand = tiles(&[2, 3, 4, 12, 13, 14, 23, 24, 25, 13, 13]);
+        assert!(evaluate(&hand, &melds, &ron(25)).unwrap().yaku.contains(&Yaku::Tanyao));
+
+        let mut ctx = ron(25);
+        ctx.kuitan = false;
+        assert!(evaluate(&hand, &melds, &ctx).is_none());
+    }
+
+    #[test]
+    fn test_sankantsu_with_closed_kans() {
+        // Ankan of 2m and 8p, open kan of 4s + concealed 666s + 77s ron on 7s
+        let kan = |kind, id| Meld { kind, tiles: tiles(&[id; 4]), called_tile: None, from: None };
+        let melds = vec![kan(MeldKind::Ankan, 1), kan(MeldKind::Ankan, 16), kan(MeldKind::Daiminkan, 21)];
+        let hand = tiles(&[23, 23, 23, 24, 24]);
+        let result = evaluate(&hand, &melds, &ron(24)).unwrap();
+        assert!(result.yaku.contains(&Yaku::Sankantsu));
+        assert!(result.yaku.contains(&Yaku::Sanankou));
+        assert!(result.yaku.contains(&Yaku::Toitoi));
+        assert!(!result.closed);
+    }
+}
@@ -1,30 +1,78 @@
 // Prevents an extra console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use std::path::PathBuf;
 use std::sync::Mutex;
 
-use tauri::{generate_context, Builder};
+use tauri::{generate_context, AppHandle, Builder, Manager, RunEvent};
 
 use tauri_practice::commands;
 use tauri_practice::commands::SharedState;
 use tauri_practice::game::game_engine::GameEngine;
+use tauri_practice::game::match_engine::MatchEngine;
+
+/// Where the game in progress is kept between runs
+fn autosave_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join("autosave.json"))
+}
 
 fn main() {
     let shared_state = SharedState {
         engine: Mutex::new(GameEngine::new()),
+        match_engine: Mutex::new(MatchEngine::new()),
+        replay: Mutex::new(None),
     };
 
     Builder::default()
         .manage(shared_state)
+        .setup(|app| {
+            // Resume the game left from the last run; a missing or incompatible autosave starts fresh
+            if let Some(path) = autosave_path(app.handle()).filter(|path| path.exists()) {
+                if let Err(e) = commands::load_from(&app.state::<SharedState>(), &path) {
+                    eprintln!("Autosave not restored: {}", e);
+                }
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::new_game,
+            commands::new_game_with_rules,
             commands::get_state,
+            commands::get_debug_state,
+            commands::get_events,
+            commands::get_shanten,
+            commands::analyze_discards,
+            commands::start_match,
+            commands::next_hand,
             commands::player_draw,
             commands::player_discard,
             commands::player_ron,
             commands::player_pass,
-            commands::cpu_step
+            commands::player_tsumo,
+            commands::player_pon,
+            commands::player_chi,
+            commands::player_daiminkan,
+            commands::player_kan,
+            commands::player_riichi,
+            commands::player_open_riichi,
+            commands::player_kyuushu,
+            commands::cpu_step,
+            commands::undo,
+            commands::get_record,
+            commands::replay_load,
+            commands::replay_seek,
+            commands::save_game,
+            commands::load_game
         ])
-        .run(generate_context!())
-        .expect("error while running tauri application");
+        .build(generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                if let Some(path) = autosave_path(app) {
+                    if let Err(e) = commands::save_to(&app.state::<SharedState>(), &path) {
+                        eprintln!("Autosave failed: {}", e);
+                    }
+                }
+            }
+        });
 }
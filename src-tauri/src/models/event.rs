@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::game_state::{MeldKind, Player, WinType, Wind};
+use crate::models::tile::Tile;
+
+/// One action at the table, recorded by the engine in the order it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GameEvent {
+    /// A hand was dealt: 13 tiles each, the dealer's first draw follows
+    Deal {
+        dealer: Player,
+        round_wind: Wind,
+        honba: u32,
+    },
+    /// A tile was drawn; `tile` is `None` when masked for another seat
+    Draw {
+        seat: Player,
+        tile: Option<Tile>,
+        /// Kan replacement tile from the dead wall
+        rinshan: bool,
+    },
+    Discard {
+        seat: Player,
+        tile: Tile,
+        /// The tile just drawn went straight out
+        tsumogiri: bool,
+    },
+    /// A meld was formed; `from` is the discarder for calls and `None` for closed and added kans
+    Call {
+        seat: Player,
+        kind: MeldKind,
+        tiles: Vec<Tile>,
+        from: Option<Player>,
+    },
+    /// Riichi declared; the sideways discard follows
+    Riichi { seat: Player, open: bool },
+    Ron { winner: Player, from: Player, tile: Tile },
+    Tsumo { winner: Player, tile: Tile },
+    /// A dora indicator was turned face up
+    DoraReveal { indicator: Tile },
+    /// The wall ran out
+    ExhaustiveDraw { tenpai: [bool; 4] },
+    /// The hand was aborted; `kind` is the abortive draw
+    Abort { kind: WinType },
+    /// Points moved at the end of a hand; `scores` are the totals afterwards
+    Score { point_changes: [i32; 4], scores: [i32; 4] },
+}
+
+impl GameEvent {
+    /// The event as `seat` may see it: other seats' drawn tiles are hidden
+    pub fn masked_for(&self, seat: Player) -> GameEvent {
+        match self {
+            GameEvent::Draw { seat: drawer, rinshan, .. } if *drawer != seat => GameEvent::Draw {
+                seat: *drawer,
+                tile: None,
+                rinshan: *rinshan,
+            },
+            event => event.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masked_for_hides_other_draws() {
+        let tile = Tile::new(5).unwrap();
+        let draw = GameEvent::Draw { seat: Player::Cpu1, tile: Some(tile), rinshan: false };
+        assert_eq!(
+            draw.masked_for(Player::Player),
+            GameEvent::Draw { seat: Player::Cpu1, tile: None, rinshan: false }
+        );
+        assert_eq!(draw.masked_for(Player::Cpu1), draw);
+
+        let discard = GameEvent::Discard { seat: Player::Cpu1, tile, tsumogiri: true };
+        assert_eq!(discard.masked_for(Player::Player), discard);
+    }
+
+    #[test]
+    fn test_event_serializes_with_type_tag() {
+        let json = serde_json::to_value(GameEvent::Riichi { seat: Player::Cpu2, open: false }).unwrap();
+        assert_eq!(json["type"], "Riichi");
+        assert_eq!(json["seat"], "Cpu2");
+    }
+}
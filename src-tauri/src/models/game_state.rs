@@ -1,5 +1,15 @@
 use serde::{Deserialize, Serialize};
 use super::tile::Tile;
+use crate::game::scoring::ScoreResult;
+use crate::game::win_checker::{MeldShape, Wait};
+use crate::models::match_state::MatchState;
+use crate::models::rules::RuleConfig;
+
+/// Points each seat starts the game with
+pub const STARTING_POINTS: i32 = 25000;
+
+/// Points deposited when declaring riichi
+pub const RIICHI_STICK: i32 = 1000;
 
 /// Game phase
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -7,6 +17,7 @@ pub enum GamePhase {
     Draw,    // Draw a tile
     Discard, // Discard a tile
     Ron,     // Ron phase (player can choose to ron or pass)
+    Call,    // Call phase (player can claim the last discard for a meld or pass)
     End,     // Game over
 }
 
@@ -29,6 +40,238 @@ impl Player {
             Player::Cpu3 => Player::Player,
         }
     }
+
+    /// Seat index used by the per-seat arrays in `GameState` [Player, Cpu1, Cpu2, Cpu3]
+    pub fn index(&self) -> usize {
+        match self {
+            Player::Player => 0,
+            Player::Cpu1 => 1,
+            Player::Cpu2 => 2,
+            Player::Cpu3 => 3,
+        }
+    }
+
+    /// Seat wind of this player when `dealer` sits East
+    pub fn seat_wind(&self, dealer: Player) -> Wind {
+        Wind::from_index(self.index() + 4 - dealer.index())
+    }
+
+    /// Player for a seat index; wraps around for indices >= 4
+    pub fn from_index(index: usize) -> Player {
+        match index % 4 {
+            0 => Player::Player,
+            1 => Player::Cpu1,
+            2 => Player::Cpu2,
+            _ => Player::Cpu3,
+        }
+    }
+}
+
+/// Kind of meld formed by calling or declaring a kan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeldKind {
+    Chi,        // Sequence claimed from the previous seat's discard
+    Pon,        // Triplet claimed from any discard
+    Daiminkan,  // Open kan: four of a kind claimed from a discard
+    Ankan,      // Closed kan: four of a kind drawn by the player
+    Shouminkan, // Added kan: a drawn tile added to an existing pon
+}
+
+/// Meld declared by a player and set aside from the concealed hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Meld {
+    pub kind: MeldKind,
+    /// All tiles of the meld, including the called tile
+    pub tiles: Vec<Tile>,
+    /// Tile claimed from another player's discard
+    pub called_tile: Option<Tile>,
+    /// Player the tile was claimed from
+    pub from: Option<Player>,
+}
+
+impl Meld {
+    /// Shape of the meld for yaku and fu (kans read as triplets)
+    pub fn shape(&self) -> MeldShape {
+        let lowest = self.tiles.iter().map(|t| t.id).min().unwrap_or(0);
+        match self.kind {
+            MeldKind::Chi => MeldShape::Sequence(lowest),
+            _ => MeldShape::Triplet(lowest),
+        }
+    }
+
+    pub fn is_kan(&self) -> bool {
+        matches!(self.kind, MeldKind::Daiminkan | MeldKind::Ankan | MeldKind::Shouminkan)
+    }
+
+    /// Whether the meld keeps the hand closed (only a closed kan does)
+    pub fn is_concealed(&self) -> bool {
+        self.kind == MeldKind::Ankan
+    }
+}
+
+/// Call a player may make on the last discard, or a kan on their own turn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallOption {
+    pub kind: MeldKind,
+    /// Tiles from the caller's hand that form the meld with the discard
+    pub tiles: Vec<Tile>,
+}
+
+/// Reasons a seat may not win by ron; tsumo is still allowed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Furiten {
+    /// One of the seat's waits is in its own discards
+    pub discard: bool,
+    /// A winning tile went by since the seat's last discard
+    pub temporary: bool,
+    /// A winning tile went by after riichi; lasts for the rest of the hand
+    pub riichi: bool,
+}
+
+impl Furiten {
+    pub fn is_furiten(&self) -> bool {
+        self.discard || self.temporary || self.riichi
+    }
+}
+
+/// Wind of a seat or of the round
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Wind {
+    East,
+    South,
+    West,
+    North,
+}
+
+impl Wind {
+    /// Offset from East (0-3)
+    pub fn index(&self) -> usize {
+        match self {
+            Wind::East => 0,
+            Wind::South => 1,
+            Wind::West => 2,
+            Wind::North => 3,
+        }
+    }
+
+    /// Wind for an offset from East; wraps around for offsets >= 4
+    pub fn from_index(index: usize) -> Wind {
+        match index % 4 {
+            0 => Wind::East,
+            1 => Wind::South,
+            2 => Wind::West,
+            _ => Wind::North,
+        }
+    }
+
+    /// Tile id of the matching wind honor tile (27-30)
+    pub fn tile_id(&self) -> u8 {
+        match self {
+            Wind::East => 27,
+            Wind::South => 28,
+            Wind::West => 29,
+            Wind::North => 30,
+        }
+    }
+}
+
+/// How a finished hand was decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WinType {
+    Ron,            // Win on another player's discard
+    Tsumo,          // Win on own draw
+    ExhaustiveDraw, // Wall ran out with no winner
+    FourWinds,      // Abortive draw: same wind discarded by all four on the first go-around
+    FourKans,       // Abortive draw: four kans by more than one seat
+    TripleRon,      // Abortive draw: three seats ron the same discard
+    NineTerminals,  // Abortive draw: nine different terminals/honors declared on the first draw
+    FourRiichi,     // Abortive draw: all four seats in riichi
+}
+
+impl WinType {
+    /// Hand stopped early with no points paid; the dealer keeps the deal
+    pub fn is_abortive_draw(&self) -> bool {
+        matches!(
+            self,
+            WinType::FourWinds | WinType::FourKans | WinType::TripleRon | WinType::NineTerminals | WinType::FourRiichi
+        )
+    }
+}
+
+/// Outcome of a finished hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameResult {
+    /// Winning player (None for a draw)
+    pub winner: Option<Player>,
+    pub win_type: WinType,
+    /// Tile that completed the winning hand (None for a draw)
+    pub winning_tile: Option<Tile>,
+    /// Player whose discard was ronned (Ron only)
+    pub deal_in: Option<Player>,
+    /// Han/fu and payment of the winning hand (None for a draw)
+    pub score: Option<ScoreResult>,
+    /// Points gained or lost by each seat [Player, Cpu1, Cpu2, Cpu3]
+    pub point_changes: [i32; 4],
+    /// Ura-dora indicators, revealed when a riichi hand wins
+    pub ura_dora_indicators: Vec<Tile>,
+    /// Seats in tenpai when the wall ran out (exhaustive draw only)
+    pub tenpai: [bool; 4],
+    /// The dealer was tenpai at the exhaustive draw and keeps the deal
+    pub dealer_tenpai: bool,
+    /// Seats paid a mangan for discarding only terminals and honors, none of them called
+    pub nagashi_mangan: Vec<Player>,
+}
+
+impl GameResult {
+    pub fn ron(winner: Player, winning_tile: Tile, deal_in: Player) -> Self {
+        GameResult {
+            winner: Some(winner),
+            win_type: WinType::Ron,
+            winning_tile: Some(winning_tile),
+            deal_in: Some(deal_in),
+            score: None,
+            point_changes: [0; 4],
+            ura_dora_indicators: Vec::new(),
+            tenpai: [false; 4],
+            dealer_tenpai: false,
+            nagashi_mangan: Vec::new(),
+        }
+    }
+
+    pub fn tsumo(winner: Player, winning_tile: Tile) -> Self {
+        GameResult {
+            winner: Some(winner),
+            win_type: WinType::Tsumo,
+            winning_tile: Some(winning_tile),
+            deal_in: None,
+            score: None,
+            point_changes: [0; 4],
+            ura_dora_indicators: Vec::new(),
+            tenpai: [false; 4],
+            dealer_tenpai: false,
+            nagashi_mangan: Vec::new(),
+        }
+    }
+
+    pub fn exhaustive_draw() -> Self {
+        GameResult::draw(WinType::ExhaustiveDraw)
+    }
+
+    /// A hand that ended without a winner
+    pub fn draw(win_type: WinType) -> Self {
+        GameResult {
+            winner: None,
+            win_type,
+            winning_tile: None,
+            deal_in: None,
+            score: None,
+            point_changes: [0; 4],
+            ura_dora_indicators: Vec::new(),
+            tenpai: [false; 4],
+            dealer_tenpai: false,
+            nagashi_mangan: Vec::new(),
+        }
+    }
 }
 
 /// Game state snapshot
@@ -47,6 +290,51 @@ pub struct GameState {
     pub drawn_tile: [Option<Tile>; 4],
     /// Last player who discarded a tile (for ron phase handling)
     pub last_discarder: Option<Player>,
+    /// Called melds for each player [Player, Cpu1, Cpu2, Cpu3]
+    pub melds: [Vec<Meld>; 4],
+    /// Calls each player may make on the last discard [Player, Cpu1, Cpu2, Cpu3]
+    pub call_options: [Vec<CallOption>; 4],
+    /// Closed or added kans the current player may declare before discarding
+    pub kan_options: [Vec<CallOption>; 4],
+    /// Current player may declare riichi with some discard [Player, Cpu1, Cpu2, Cpu3]
+    pub can_riichi: [bool; 4],
+    /// Current player may declare kyuushu kyuuhai (nine terminals) [Player, Cpu1, Cpu2, Cpu3]
+    pub can_kyuushu: [bool; 4],
+    /// Riichi declared [Player, Cpu1, Cpu2, Cpu3]
+    pub riichi: [bool; 4],
+    /// Riichi declared on the first uninterrupted turn
+    pub double_riichi: [bool; 4],
+    /// Riichi declared with the hand shown (open riichi rule)
+    pub open_riichi: [bool; 4],
+    /// Riichi still within its first go-around (no call, no later discard)
+    pub ippatsu: [bool; 4],
+    /// Index in `discards` of each seat's sideways riichi tile
+    pub riichi_discard: [Option<usize>; 4],
+    /// 1000-point riichi sticks on the table, collected by the next winner
+    pub riichi_sticks: u32,
+    /// Furiten status [Player, Cpu1, Cpu2, Cpu3]
+    pub furiten: [Furiten; 4],
+    /// Tiles completing the human player's hand, with the copies they have not seen; empty unless tenpai
+    pub waits: Vec<Wait>,
+    /// Outcome of the hand (set when the phase becomes End)
+    pub result: Option<GameResult>,
+    /// Further ron wins on the same discard under double ron, in turn order after `result`
+    pub additional_results: Vec<GameResult>,
+    /// Dora indicators turned face up on the dead wall
+    pub dora_indicators: Vec<Tile>,
+    /// Point totals for each player [Player, Cpu1, Cpu2, Cpu3]
+    pub scores: [i32; 4],
+    /// Player sitting East for this hand
+    pub dealer: Player,
+    pub round_wind: Wind,
+    /// Repeat counter; each honba adds 300 points to a win
+    pub honba: u32,
+    /// Match progress when the hand is part of a match
+    pub match_state: Option<MatchState>,
+    /// Rules the hand is played under
+    pub rules: RuleConfig,
+    /// Seed the game's RNG started from; the same seed and inputs replay the same game
+    pub seed: u64,
 }
 
 impl GameState {
@@ -62,6 +350,29 @@ impl GameState {
             can_ron: [false; 4],
             drawn_tile: [None; 4],
             last_discarder: None,
+            melds: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            call_options: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            kan_options: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            can_riichi: [false; 4],
+            can_kyuushu: [false; 4],
+            riichi: [false; 4],
+            double_riichi: [false; 4],
+            open_riichi: [false; 4],
+            ippatsu: [false; 4],
+            riichi_discard: [None; 4],
+            riichi_sticks: 0,
+            furiten: [Furiten::default(); 4],
+            waits: Vec::new(),
+            result: None,
+            additional_results: Vec::new(),
+            dora_indicators: Vec::new(),
+            scores: [STARTING_POINTS; 4],
+            dealer: Player::Player,
+            round_wind: Wind::East,
+            honba: 0,
+            match_state: None,
+            rules: RuleConfig::default(),
+            seed: 0,
         }
     }
 }
@@ -78,6 +389,81 @@ mod tests {
         assert_eq!(Player::Cpu3.next(), Player::Player);
     }
 
+    #[test]
+    fn test_player_index_round_trip() {
+        for index in 0..4 {
+            assert_eq!(Player::from_index(index).index(), index);
+        }
+        assert_eq!(Player::from_index(4), Player::Player);
+    }
+
+    #[test]
+    fn test_seat_wind() {
+        assert_eq!(Player::Player.seat_wind(Player::Player), Wind::East);
+        assert_eq!(Player::Cpu3.seat_wind(Player::Player), Wind::North);
+        assert_eq!(Player::Player.seat_wind(Player::Cpu1), Wind::North);
+        assert_eq!(Player::Cpu2.seat_wind(Player::Cpu1), Wind::South);
+    }
+
+    #[test]
+    fn test_meld_shape() {
+        let chi = Meld {
+            kind: MeldKind::Chi,
+            tiles: vec![Tile::new(4).unwrap(), Tile::new(2).unwrap(), Tile::new(3).unwrap()],
+            called_tile: Some(Tile::new(4).unwrap()),
+            from: Some(Player::Cpu3),
+        };
+        assert_eq!(chi.shape(), MeldShape::Sequence(2));
+        assert!(!chi.is_concealed());
+
+        let kan = Meld {
+            kind: MeldKind::Daiminkan,
+            tiles: vec![Tile::new(31).unwrap(); 4],
+            called_tile: Some(Tile::new(31).unwrap()),
+            from: Some(Player::Cpu1),
+        };
+        assert_eq!(kan.shape(), MeldShape::Triplet(31));
+        assert!(kan.is_kan());
+        assert!(!kan.is_concealed());
+
+        let ankan = Meld { kind: MeldKind::Ankan, called_tile: None, from: None, ..kan };
+        assert!(ankan.is_kan());
+        assert!(ankan.is_concealed());
+    }
+
+    #[test]
+    fn test_furiten() {
+        assert!(!Furiten::default().is_furiten());
+        assert!(Furiten { temporary: true, ..Default::default() }.is_furiten());
+    }
+
+    #[test]
+    fn test_wind_tile_id() {
+        assert_eq!(Wind::from_index(0), Wind::East);
+        assert_eq!(Wind::from_index(5), Wind::South);
+        assert_eq!(Wind::West.index(), 2);
+        assert_eq!(Wind::East.tile_id(), 27);
+        assert_eq!(Wind::North.tile_id(), 30);
+    }
+
+    #[test]
+    fn test_game_result_constructors() {
+        let tile = Tile::new(27).unwrap();
+        let ron = GameResult::ron(Player::Cpu2, tile, Player::Player);
+        assert_eq!(ron.winner, Some(Player::Cpu2));
+        assert_eq!(ron.win_type, WinType::Ron);
+        assert_eq!(ron.deal_in, Some(Player::Player));
+
+        let tsumo = GameResult::tsumo(Player::Player, tile);
+        assert_eq!(tsumo.win_type, WinType::Tsumo);
+        assert_eq!(tsumo.winning_tile, Some(tile));
+        assert_eq!(tsumo.deal_in, None);
+
+        let draw = GameResult::exhaustive_draw();
+        assert_eq!(draw.winner, None);
+        assert_eq!(draw.win_type, WinType::ExhaustiveDraw);
+    }
+
     #[test]
     fn test_game_state_new() {
         let state = GameState::new();
@@ -86,6 +472,9 @@ mod tests {
         assert_eq!(state.wall_count, 136);
         assert_eq!(state.current_player, Player::Player);
         assert_eq!(state.phase, GamePhase::Draw);
+        assert_eq!(state.result, None);
+        assert_eq!(state.scores, [STARTING_POINTS; 4]);
+        assert_eq!(state.dealer, Player::Player);
     }
 }
 
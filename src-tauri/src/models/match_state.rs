@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::game_state::{Player, Wind};
+
+/// Number of rounds played in a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MatchLength {
+    /// Tonpuusen: East round only
+    EastOnly,
+    /// Hanchan: East and South rounds
+    #[default]
+    EastSouth,
+}
+
+impl MatchLength {
+    /// Last round wind played in a match of this length
+    pub fn last_round(&self) -> Wind {
+        match self {
+            MatchLength::EastOnly => Wind::East,
+            MatchLength::EastSouth => Wind::South,
+        }
+    }
+}
+
+/// Progress of a match across hands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchState {
+    pub length: MatchLength,
+    pub round_wind: Wind,
+    /// Seat that dealt the first hand of the match
+    pub first_dealer: Player,
+    pub dealer: Player,
+    /// Hand within the round (1-4), e.g. East 2
+    pub hand_number: u8,
+    /// Repeat counter, raised by renchan and draws
+    pub honba: u32,
+    /// Set once the last hand has been played or a seat went below zero
+    pub finished: bool,
+    /// Seats from first to last place, filled when the match finishes
+    pub ranking: Vec<Player>,
+}
+
+impl MatchState {
+    pub fn new(length: MatchLength, first_dealer: Player) -> Self {
+        MatchState {
+            length,
+            round_wind: Wind::East,
+            first_dealer,
+            dealer: first_dealer,
+            hand_number: 1,
+            honba: 0,
+            finished: false,
+            ranking: Vec::new(),
+        }
+    }
+}
+
+/// Seats ordered by points, ties broken by seating order from the first dealer
+pub fn rank_seats(scores: &[i32; 4], first_dealer: Player) -> Vec<Player> {
+    let mut seats: Vec<Player> = (0..4).map(|offset| Player::from_index(first_dealer.index() + offset)).collect();
+    seats.sort_by_key(|seat| std::cmp::Reverse(scores[seat.index()]));
+    seats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_seats() {
+        let ranking = rank_seats(&[25000, 30000, 25000, 20000], Player::Cpu2);
+        assert_eq!(ranking, vec![Player::Cpu1, Player::Cpu2, Player::Player, Player::Cpu3]);
+    }
+
+    #[test]
+    fn test_last_round() {
+        assert_eq!(MatchLength::EastOnly.last_round(), Wind::East);
+        assert_eq!(MatchLength::EastSouth.last_round(), Wind::South);
+    }
+}
@@ -1,3 +1,7 @@
 pub mod tile;
 pub mod game_state;
-
+pub mod match_state;
+pub mod rules;
+pub mod player_view;
+pub mod event;
+pub mod record;
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::game_state::{GamePhase, GameState, Player};
+
+/// The game as one seat sees it: opponents' concealed tiles and private flags
+/// are masked, while how many tiles each seat holds stays visible
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerView {
+    #[serde(flatten)]
+    pub state: GameState,
+    /// Seat the view belongs to
+    pub seat: Player,
+    /// Concealed tiles in each hand, not counting a drawn tile
+    pub hand_counts: [usize; 4],
+    /// Seats currently holding a drawn tile
+    pub has_drawn_tile: [bool; 4],
+}
+
+impl PlayerView {
+    /// Mask `state` for `seat`. Hands are shown once the hand is over, and an open riichi
+    /// hand is public; the seed is withheld until the game is over since it fixes the walls.
+    pub fn new(state: &GameState, seat: Player) -> Self {
+        let hand_counts = std::array::from_fn(|i| state.hands[i].len());
+        let has_drawn_tile = std::array::from_fn(|i| state.drawn_tile[i].is_some());
+        let hand_over = state.phase == GamePhase::End;
+        let game_over = hand_over && state.match_state.as_ref().is_none_or(|m| m.finished);
+
+        let mut state = state.clone();
+        for i in (0..4).filter(|&i| i != seat.index()) {
+            if !hand_over {
+                if !state.open_riichi[i] {
+                    state.hands[i].clear();
+                }
+                state.drawn_tile[i] = None;
+            }
+            state.can_tsumo[i] = false;
+            state.can_ron[i] = false;
+            state.can_riichi[i] = false;
+            state.can_kyuushu[i] = false;
+            state.call_options[i].clear();
+            state.kan_options[i].clear();
+            state.furiten[i] = Default::default();
+        }
+        if seat != Player::Player {
+            state.waits.clear();
+        }
+        if !game_over {
+            state.seed = 0;
+        }
+
+        PlayerView {
+            state,
+            seat,
+            hand_counts,
+            has_drawn_tile,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::match_state::{MatchLength, MatchState};
+    use crate::models::tile::Tile;
+
+    fn dealt_state() -> GameState {
+        let mut state = GameState::new();
+        for (i, hand) in state.hands.iter_mut().enumerate() {
+            *hand = (0..13).map(|id| Tile::new(id + i as u8).unwrap()).collect();
+        }
+        state.drawn_tile[1] = Some(Tile::new(30).unwrap());
+        state.can_ron = [true; 4];
+        state.phase = GamePhase::Discard;
+        state.seed = 42;
+        state
+    }
+
+    #[test]
+    fn test_view_masks_opponents() {
+        let state = dealt_state();
+        let view = PlayerView::new(&state, Player::Player);
+
+        assert_eq!(view.state.hands[0], state.hands[0]);
+        assert!(view.state.hands[1..].iter().all(|hand| hand.is_empty()));
+        assert_eq!(view.hand_counts, [13; 4]);
+        assert_eq!(view.state.drawn_tile[1], None);
+        assert_eq!(view.has_drawn_tile, [false, true, false, false]);
+        assert_eq!(view.state.can_ron, [true, false, false, false]);
+        assert_eq!(view.state.seed, 0);
+
+        // Another seat's view shows only its own hand
+        let view = PlayerView::new(&state, Player::Cpu1);
+        assert!(view.state.hands[0].is_empty());
+        assert_eq!(view.state.drawn_tile[1], state.drawn_tile[1]);
+    }
+
+    #[test]
+    fn test_open_riichi_hand_is_public() {
+        let mut state = dealt_state();
+        state.open_riichi[2] = true;
+        let view = PlayerView::new(&state, Player::Player);
+        assert_eq!(view.state.hands[2], state.hands[2]);
+        assert!(view.state.hands[1].is_empty());
+    }
+
+    #[test]
+    fn test_hands_and_seed_revealed_at_end() {
+        let mut state = dealt_state();
+        state.phase = GamePhase::End;
+        let view = PlayerView::new(&state, Player::Player);
+        assert_eq!(view.state.hands, state.hands);
+        assert_eq!(view.state.seed, 42);
+
+        // Mid-match the seed would give away the later walls
+        state.match_state = Some(MatchState::new(MatchLength::EastOnly, Player::Player));
+        assert_eq!(PlayerView::new(&state, Player::Player).state.seed, 0);
+    }
+
+    #[test]
+    fn test_view_serializes_flat() {
+        let json = serde_json::to_value(PlayerView::new(&dealt_state(), Player::Player)).unwrap();
+        assert_eq!(json["hand_counts"][1], 13);
+        assert_eq!(json["seat"], "Player");
+        assert!(json["hands"].is_array());
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::rules::RuleConfig;
+use crate::models::tile::Tile;
+
+/// An input to the game: a decision by the human player, a CPU step or moving on to the next hand
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Action {
+    PlayerDraw,
+    PlayerDiscard { tile: Tile },
+    PlayerRiichi { tile: Tile },
+    PlayerOpenRiichi { tile: Tile },
+    PlayerRon,
+    PlayerTsumo,
+    PlayerPon,
+    PlayerChi { tiles: Vec<Tile> },
+    PlayerDaiminkan,
+    PlayerKan { tile: Tile },
+    PlayerKyuushu,
+    PlayerPass,
+    CpuStep,
+    /// Deal the next hand of a match
+    NextHand,
+}
+
+/// Everything needed to play a game again: how it started and every action since.
+/// The seed fixes the walls and CPU choices, so the same actions give the same game.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub seed: u64,
+    pub rules: RuleConfig,
+    /// Played as a match rather than a standalone hand
+    pub is_match: bool,
+    pub actions: Vec<Action>,
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::game_state::STARTING_POINTS;
+use crate::models::match_state::MatchLength;
+use crate::models::tile::RedFives;
+
+/// House rules for a game, chosen when it starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleConfig {
+    /// Red fives in the wall, each worth a dora
+    pub red_fives: RedFives,
+    /// Tanyao counts for open hands
+    pub kuitan: bool,
+    pub ron_resolution: RonResolution,
+    pub abortive_draws: AbortiveDraws,
+    pub starting_points: i32,
+    pub match_length: MatchLength,
+    /// The match ends as soon as a seat drops below zero
+    pub busting: bool,
+    /// Riichi may be declared with the hand shown, for one more han
+    pub open_riichi: bool,
+    /// Practice mode: the player may take back discards
+    pub practice: bool,
+}
+
+impl Default for RuleConfig {
+    fn default() -> Self {
+        RuleConfig {
+            red_fives: RedFives::default(),
+            kuitan: true,
+            ron_resolution: RonResolution::default(),
+            abortive_draws: AbortiveDraws::default(),
+            starting_points: STARTING_POINTS,
+            match_length: MatchLength::default(),
+            busting: true,
+            open_riichi: false,
+            practice: false,
+        }
+    }
+}
+
+impl RuleConfig {
+    /// Reject rule sets the engine cannot play
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1000..=1_000_000).contains(&self.starting_points) {
+            return Err("Starting points must be between 1000 and 1000000".into());
+        }
+        if self.starting_points % 100 != 0 {
+            return Err("Starting points must be a multiple of 100".into());
+        }
+        Ok(())
+    }
+}
+
+/// How several rons on the same discard are settled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RonResolution {
+    /// Atamahane: only the claimant closest in turn order after the discarder wins
+    #[default]
+    HeadBump,
+    /// Every claimant wins and is paid by the discarder
+    DoubleRon,
+}
+
+/// Abortive draws (tochuu ryuukyoku) in effect; each one can be switched off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbortiveDraws {
+    /// Suufon renda
+    pub four_winds: bool,
+    /// Suukaikan
+    pub four_kans: bool,
+    /// Sanchahou
+    pub triple_ron: bool,
+    /// Kyuushu kyuuhai, declared by the seat itself
+    pub nine_terminals: bool,
+    /// Suucha riichi
+    pub four_riichi: bool,
+}
+
+impl Default for AbortiveDraws {
+    fn default() -> Self {
+        AbortiveDraws {
+            four_winds: true,
+            four_kans: true,
+            triple_ron: true,
+            nine_terminals: true,
+            four_riichi: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules_are_valid() {
+        assert_eq!(RuleConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_starting_points() {
+        let rules = RuleConfig { starting_points: 0, ..RuleConfig::default() };
+        assert!(rules.validate().is_err());
+        let rules = RuleConfig { starting_points: 25050, ..RuleConfig::default() };
+        assert!(rules.validate().is_err());
+        let rules = RuleConfig { starting_points: 30000, ..RuleConfig::default() };
+        assert!(rules.validate().is_ok());
+    }
+
+    #[test]
+    fn test_rules_round_trip_through_json() {
+        let rules = RuleConfig { kuitan: false, open_riichi: true, ..RuleConfig::default() };
+        let json = serde_json::to_string(&rules).unwrap();
+        assert_eq!(serde_json::from_str::<RuleConfig>(&json).unwrap(), rules);
+    }
+}
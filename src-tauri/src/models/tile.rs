@@ -1,3 +1,4 @@
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// Tile ID (0-33)
@@ -8,6 +9,9 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Tile {
     pub id: u8,
+    /// Red five (aka-dora); copies of a tile otherwise compare equal
+    #[serde(default)]
+    pub red: bool,
 }
 
 impl Tile {
@@ -16,7 +20,15 @@ impl Tile {
         if id > 33 {
             return Err(format!("Invalid tile ID: {}", id));
         }
-        Ok(Tile { id })
+        Ok(Tile { id, red: false })
+    }
+
+    /// Create a red five; returns error if id is not a suited 5
+    pub fn red_five(id: u8) -> Result<Self, String> {
+        if !matches!(id, 4 | 13 | 22) {
+            return Err(format!("Tile ID {} cannot be a red five", id));
+        }
+        Ok(Tile { id, red: true })
     }
 
     /// Get tile suit
@@ -54,6 +66,11 @@ impl Tile {
         }
     }
 
+    /// 1s, 9s and honors (yaochuuhai)
+    pub fn is_terminal_or_honor(&self) -> bool {
+        matches!(self.number(), None | Some(1) | Some(9))
+    }
+
     /// Sort key (Manzu -> Pinzu -> Souzu -> Honors)
     pub fn sort_order(&self) -> u8 {
         self.id
@@ -81,32 +98,54 @@ pub enum HonorTile {
     Chun,  // Red
 }
 
-/// Create full wall (136 tiles, 4 copies each)
-pub fn create_wall() -> Vec<Tile> {
+/// Number of red fives in the wall
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RedFives {
+    Zero,
+    /// One red five per suit
+    #[default]
+    Three,
+    /// One red 5m and 5s, two red 5p
+    Four,
+}
+
+impl RedFives {
+    /// Red copies of the 5 with the given id
+    fn copies(&self, id: u8) -> usize {
+        match (self, id) {
+            (RedFives::Zero, _) => 0,
+            (RedFives::Four, 13) => 2,
+            (_, 4 | 13 | 22) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Create full wall (136 tiles, 4 copies each) with `red_fives` of the 5s marked red
+pub fn create_wall(red_fives: RedFives) -> Vec<Tile> {
     let mut wall = Vec::with_capacity(136);
     
     // Add 4 copies of each tile id (0-33)
     for id in 0..=33 {
-        for _ in 0..4 {
-            wall.push(Tile { id });
+        let red = red_fives.copies(id);
+        for copy in 0..4 {
+            wall.push(Tile { id, red: copy < red });
         }
     }
     
     wall
 }
 
-/// Shuffle wall in place
-pub fn shuffle_wall(wall: &mut Vec<Tile>) {
+/// Shuffle wall in place with `rng`
+pub fn shuffle_wall<R: Rng>(wall: &mut Vec<Tile>, rng: &mut R) {
     use rand::seq::SliceRandom;
-    use rand::thread_rng;
-    
-    let mut rng = thread_rng();
-    wall.shuffle(&mut rng);
+
+    wall.shuffle(rng);
 }
 
-/// Sort a hand (Manzu -> Pinzu -> Souzu -> Honors)
+/// Sort a hand (Manzu -> Pinzu -> Souzu -> Honors), red fives after plain ones
 pub fn sort_hand(hand: &mut Vec<Tile>) {
-    hand.sort_by_key(|tile| tile.sort_order());
+    hand.sort_by_key(|tile| (tile.sort_order(), tile.red));
 }
 
 #[cfg(test)]
@@ -148,9 +187,17 @@ mod tests {
         assert_eq!(Tile::new(0).unwrap().honor(), None);
     }
 
+    #[test]
+    fn test_terminal_or_honor() {
+        assert!(Tile::new(0).unwrap().is_terminal_or_honor()); // 1m
+        assert!(Tile::new(17).unwrap().is_terminal_or_honor()); // 9p
+        assert!(Tile::new(31).unwrap().is_terminal_or_honor()); // White
+        assert!(!Tile::new(19).unwrap().is_terminal_or_honor()); // 2s
+    }
+
     #[test]
     fn test_create_wall() {
-        let wall = create_wall();
+        let wall = create_wall(RedFives::Zero);
         assert_eq!(wall.len(), 136);
         
         // Ensure each tile id appears exactly 4 times
@@ -163,6 +210,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_wall_red_fives() {
+        let red = |red_fives| -> Vec<u8> {
+            create_wall(red_fives).iter().filter(|t| t.red).map(|t| t.id).collect()
+        };
+        assert!(red(RedFives::Zero).is_empty());
+        assert_eq!(red(RedFives::Three), vec![4, 13, 22]);
+        assert_eq!(red(RedFives::Four), vec![4, 13, 13, 22]);
+        assert_eq!(create_wall(RedFives::Four).len(), 136);
+    }
+
+    #[test]
+    fn test_shuffle_wall_is_seeded() {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        let shuffled = |seed| {
+            let mut wall = create_wall(RedFives::Three);
+            shuffle_wall(&mut wall, &mut ChaCha8Rng::seed_from_u64(seed));
+            wall
+        };
+        assert_eq!(shuffled(7), shuffled(7));
+        assert_ne!(shuffled(7), shuffled(8));
+    }
+
+    #[test]
+    fn test_red_five() {
+        let red = Tile::red_five(13).unwrap();
+        assert_eq!(red.number(), Some(5));
+        assert_eq!(red.suit(), TileSuit::Pinzu);
+        assert_ne!(red, Tile::new(13).unwrap());
+        assert!(Tile::red_five(5).is_err());
+
+        // Older serialized tiles without the flag are plain
+        let tile: Tile = serde_json::from_str(r#"{"id":4}"#).unwrap();
+        assert!(!tile.red);
+    }
+
     #[test]
     fn test_sort_hand() {
         let mut hand = vec![
@@ -1,20 +1,28 @@
 import { useState, useEffect, useRef, useCallback } from "preact/hooks";
-import { GameState } from "./types";
+import { CallOption, DiscardOption, MatchLength, PlayerView, RuleConfig, Tile } from "./types";
 import { safeInvoke } from "./lib/tauri";
 import { HandDisplay } from "./components/HandDisplay";
 import { DiscardDisplay } from "./components/DiscardDisplay";
 import { DebugPanel } from "./components/DebugPanel";
 import { GameEndModal } from "./components/GameEndModal";
+import { RuleSettings } from "./components/RuleSettings";
+import { ReplayPanel } from "./components/ReplayPanel";
+import { TileDisplay } from "./components/TileDisplay";
 
 export function App() {
-  const [gameState, setGameState] = useState<GameState | null>(null);
+  const [gameState, setGameState] = useState<PlayerView | null>(null);
   const [debugOpen, setDebugOpen] = useState(false);
+  const [replayOpen, setReplayOpen] = useState(false);
   const [showEndModal, setShowEndModal] = useState(false);
-  const [hideCpuTiles, setHideCpuTiles] = useState(true); // Hide CPU tiles by default
+  const [showRules, setShowRules] = useState(false);
+  const [riichiMode, setRiichiMode] = useState<"closed" | "open" | null>(null); // Next tile click declares riichi
+  const [shanten, setShanten] = useState<number | null>(null);
+  const [showHints, setShowHints] = useState(false);
+  const [hints, setHints] = useState<DiscardOption[]>([]);
   const isProcessingRef = useRef(false);
 
   const loadGameState = async () => {
-    const state = await safeInvoke<GameState>("get_state", undefined, { showAlert: false });
+    const state = await safeInvoke<PlayerView>("get_state", undefined, { showAlert: false });
     if (state) {
       setGameState(state);
     }
@@ -23,14 +31,74 @@ export function App() {
   const startNewGame = async () => {
     setShowEndModal(false);
     isProcessingRef.current = false;
-    const state = await safeInvoke<GameState>("new_game");
+    const state = await safeInvoke<PlayerView>("new_game");
     if (state) {
       setGameState(state);
     }
   };
 
-  const handleDiscard = async (tile: { id: number }) => {
-    const state = await safeInvoke<GameState>("player_discard", { tileId: tile.id });
+  const saveGame = async () => {
+    const path = window.prompt("Save game to file:");
+    if (path) {
+      await safeInvoke<void>("save_game", { path });
+    }
+  };
+
+  const loadGame = async () => {
+    const path = window.prompt("Load game from file:");
+    if (!path) return;
+    setShowEndModal(false);
+    isProcessingRef.current = false;
+    const state = await safeInvoke<PlayerView>("load_game", { path });
+    if (state) {
+      setGameState(state);
+    }
+  };
+
+  // Take back the last discard, along with the CPU turns that followed it
+  const undo = async () => {
+    setShowEndModal(false);
+    setRiichiMode(null);
+    isProcessingRef.current = false;
+    const state = await safeInvoke<PlayerView>("undo");
+    if (state) {
+      setGameState(state);
+    }
+  };
+
+  const startMatch = async (length: MatchLength) => {
+    setShowEndModal(false);
+    isProcessingRef.current = false;
+    const state = await safeInvoke<PlayerView>("start_match", { length });
+    if (state) {
+      setGameState(state);
+    }
+  };
+
+  const startWithRules = async (rules: RuleConfig) => {
+    setShowEndModal(false);
+    setShowRules(false);
+    isProcessingRef.current = false;
+    const state = await safeInvoke<PlayerView>("new_game_with_rules", { rules });
+    if (state) {
+      setGameState(state);
+    }
+  };
+
+  const nextHand = async () => {
+    setShowEndModal(false);
+    isProcessingRef.current = false;
+    const state = await safeInvoke<PlayerView>("next_hand");
+    if (state) {
+      setGameState(state);
+    }
+  };
+
+  const handleDiscard = async (tile: Tile) => {
+    const command =
+      riichiMode === "open" ? "player_open_riichi" : riichiMode === "closed" ? "player_riichi" : "player_discard";
+    setRiichiMode(null);
+    const state = await safeInvoke<PlayerView>(command, { tile });
     if (state) {
       setGameState(state);
     }
@@ -54,7 +122,7 @@ export function App() {
     
     isProcessingRef.current = true;
     
-    const state = await safeInvoke<GameState>("player_draw", undefined, {
+    const state = await safeInvoke<PlayerView>("player_draw", undefined, {
       showAlert: false,
       onError: (error) => {
         // If error is "Game has ended", show end modal
@@ -95,7 +163,7 @@ export function App() {
     
     isProcessingRef.current = true;
     
-    const state = await safeInvoke<GameState>("cpu_step", undefined, {
+    const state = await safeInvoke<PlayerView>("cpu_step", undefined, {
       showAlert: false,
       onError: (error) => {
         // If error is "Game has ended", show end modal
@@ -117,12 +185,25 @@ export function App() {
   }, [gameState]);
 
   const handleTsumo = async () => {
-    // TODO: Implement tsumo command
-    alert("Tsumo win! (Not yet implemented)");
+    const state = await safeInvoke<PlayerView>("player_tsumo");
+    if (state) {
+      setGameState(state);
+      if (state.phase === "End") {
+        setShowEndModal(true);
+      }
+    }
+  };
+
+  const handleKyuushu = async () => {
+    const state = await safeInvoke<PlayerView>("player_kyuushu");
+    if (state) {
+      setGameState(state);
+      setShowEndModal(true);
+    }
   };
 
   const handleRon = async () => {
-    const state = await safeInvoke<GameState>("player_ron");
+    const state = await safeInvoke<PlayerView>("player_ron");
     if (state) {
       setGameState(state);
       if (state.phase === "End") {
@@ -131,8 +212,25 @@ export function App() {
     }
   };
 
+  const handleCall = async (option: CallOption) => {
+    const state =
+      option.kind === "Chi"
+        ? await safeInvoke<PlayerView>("player_chi", { tiles: option.tiles })
+        : await safeInvoke<PlayerView>(option.kind === "Pon" ? "player_pon" : "player_daiminkan");
+    if (state) {
+      setGameState(state);
+    }
+  };
+
+  const handleKan = async (option: CallOption) => {
+    const state = await safeInvoke<PlayerView>("player_kan", { tile: option.tiles[0] });
+    if (state) {
+      setGameState(state);
+    }
+  };
+
   const handlePass = async () => {
-    const state = await safeInvoke<GameState>("player_pass");
+    const state = await safeInvoke<PlayerView>("player_pass");
     if (state) {
       setGameState(state);
     }
@@ -142,6 +240,23 @@ export function App() {
     loadGameState();
   }, []);
 
+  // Keep the player's shanten in step with their hand
+  useEffect(() => {
+    if (!gameState) return;
+    safeInvoke<number>("get_shanten", undefined, { showAlert: false }).then(setShanten);
+  }, [gameState]);
+
+  // Rank the player's discards while hints are on and it is their discard
+  useEffect(() => {
+    if (!gameState || !showHints || gameState.current_player !== "Player" || gameState.phase !== "Discard") {
+      setHints([]);
+      return;
+    }
+    safeInvoke<DiscardOption[]>("analyze_discards", undefined, { showAlert: false }).then((options) =>
+      setHints(options ?? [])
+    );
+  }, [gameState, showHints]);
+
   // Auto-process player draw phase
   useEffect(() => {
     if (!gameState) return;
@@ -201,10 +316,17 @@ export function App() {
           <div className="player-area player-top">
             <div className="player-area-rotated player-rotate-180">
               <div className="p-1 rounded-lg mb-1">
-                <DiscardDisplay tiles={gameState.discards[2]} title="CPU2 Discards" />
+                <DiscardDisplay tiles={gameState.discards[2]} title="CPU2 Discards" riichiIndex={gameState.riichi_discard[2]} />
               </div>
               <div className="p-1 rounded-lg">
-                <HandDisplay tiles={gameState.hands[2]} drawnTile={gameState.drawn_tile[2]} title="CPU2" hidden={hideCpuTiles} />
+                <HandDisplay
+                  tiles={gameState.hands[2]}
+                  drawnTile={gameState.drawn_tile[2]}
+                  concealedCount={gameState.hand_counts[2]}
+                  hasDrawnTile={gameState.has_drawn_tile[2]}
+                  title="CPU2"
+                  melds={gameState.melds[2]}
+                />
               </div>
             </div>
           </div>
@@ -213,10 +335,17 @@ export function App() {
           <div className="player-area player-left">
             <div className="player-area-rotated player-rotate-90">
               <div className="p-1 rounded-lg mr-1">
-                <DiscardDisplay tiles={gameState.discards[3]} title="CPU3 Discards" />
+                <DiscardDisplay tiles={gameState.discards[3]} title="CPU3 Discards" riichiIndex={gameState.riichi_discard[3]} />
               </div>
               <div className="p-1 rounded-lg">
-                <HandDisplay tiles={gameState.hands[3]} drawnTile={gameState.drawn_tile[3]} title="CPU3" hidden={hideCpuTiles} />
+                <HandDisplay
+                  tiles={gameState.hands[3]}
+                  drawnTile={gameState.drawn_tile[3]}
+                  concealedCount={gameState.hand_counts[3]}
+                  hasDrawnTile={gameState.has_drawn_tile[3]}
+                  title="CPU3"
+                  melds={gameState.melds[3]}
+                />
               </div>
             </div>
           </div>
@@ -231,9 +360,31 @@ export function App() {
                 <p className="text-sm mb-2">
                   <strong>Phase:</strong> {gameState.phase}
                 </p>
-                <p className="text-sm mb-4">
+                <p className="text-sm mb-2">
                   <strong>Wall Count:</strong> {gameState.wall_count}
                 </p>
+                {gameState.match_state && (
+                  <p className="text-sm mb-2">
+                    <strong>Hand:</strong> {gameState.match_state.round_wind} {gameState.match_state.hand_number}
+                  </p>
+                )}
+                <p className="text-sm mb-2">
+                  <strong>Honba:</strong> {gameState.honba}
+                </p>
+                <p className="text-sm mb-2">
+                  <strong>Riichi Sticks:</strong> {gameState.riichi_sticks}
+                </p>
+                {shanten !== null && (
+                  <p className="text-sm mb-4">
+                    <strong>Shanten:</strong> {shanten === -1 ? "Complete" : shanten === 0 ? "Tenpai" : shanten}
+                  </p>
+                )}
+                <div className="flex gap-1 justify-center items-center mb-4">
+                  <strong className="text-sm">Dora:</strong>
+                  {gameState.dora_indicators.map((tile, index) => (
+                    <TileDisplay key={`dora-${index}`} tile={tile} size="small" />
+                  ))}
+                </div>
                 <div className="flex gap-2 justify-center mb-2">
                   <button
                     onClick={startNewGame}
@@ -241,24 +392,68 @@ export function App() {
                   >
                     New Game
                   </button>
+                  <button
+                    onClick={() => startMatch("EastOnly")}
+                    className="bg-blue-500 text-white px-4 py-2 rounded hover:bg-blue-600 text-sm"
+                  >
+                    East Match
+                  </button>
+                  <button
+                    onClick={() => startMatch("EastSouth")}
+                    className="bg-blue-500 text-white px-4 py-2 rounded hover:bg-blue-600 text-sm"
+                  >
+                    Hanchan
+                  </button>
+                  <button
+                    onClick={() => setShowRules(!showRules)}
+                    className="bg-blue-500 text-white px-4 py-2 rounded hover:bg-blue-600 text-sm"
+                  >
+                    Rules
+                  </button>
+                  <button
+                    onClick={() => setShowHints(!showHints)}
+                    className={`${showHints ? "bg-green-600" : "bg-gray-500"} text-white px-4 py-2 rounded hover:bg-gray-600 text-sm`}
+                  >
+                    Hints
+                  </button>
                   <button
                     onClick={loadGameState}
                     className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600 text-sm"
                   >
                     Refresh
                   </button>
+                  <button
+                    onClick={() => setReplayOpen(true)}
+                    className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600 text-sm"
+                  >
+                    Replay
+                  </button>
+                  <button
+                    onClick={saveGame}
+                    className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600 text-sm"
+                  >
+                    Save
+                  </button>
+                  <button
+                    onClick={loadGame}
+                    className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600 text-sm"
+                  >
+                    Load
+                  </button>
+                  {gameState.rules.practice && (
+                    <button
+                      onClick={undo}
+                      className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600 text-sm"
+                    >
+                      Undo
+                    </button>
+                  )}
                 </div>
-                <div className="flex gap-2 justify-center items-center">
-                  <label className="text-sm flex items-center gap-2 cursor-pointer">
-                    <input
-                      type="checkbox"
-                      checked={hideCpuTiles}
-                      onChange={(e) => setHideCpuTiles(e.currentTarget.checked)}
-                      className="w-4 h-4"
-                    />
-                    <span>Hide CPU Tiles</span>
-                  </label>
-                </div>
+                {showRules && (
+                  <div className="flex justify-center mb-2">
+                    <RuleSettings onStart={startWithRules} />
+                  </div>
+                )}
               </div>
             </div>
           </div>
@@ -267,10 +462,17 @@ export function App() {
           <div className="player-area player-right">
             <div className="player-area-rotated player-rotate-270">
               <div className="p-1 rounded-lg ml-1">
-                <DiscardDisplay tiles={gameState.discards[1]} title="CPU1 Discards" />
+                <DiscardDisplay tiles={gameState.discards[1]} title="CPU1 Discards" riichiIndex={gameState.riichi_discard[1]} />
               </div>
               <div className="p-1 rounded-lg">
-                <HandDisplay tiles={gameState.hands[1]} drawnTile={gameState.drawn_tile[1]} title="CPU1" hidden={hideCpuTiles} />
+                <HandDisplay
+                  tiles={gameState.hands[1]}
+                  drawnTile={gameState.drawn_tile[1]}
+                  concealedCount={gameState.hand_counts[1]}
+                  hasDrawnTile={gameState.has_drawn_tile[1]}
+                  title="CPU1"
+                  melds={gameState.melds[1]}
+                />
               </div>
             </div>
           </div>
@@ -281,19 +483,34 @@ export function App() {
               <DiscardDisplay
                 tiles={gameState.discards[0]}
                 title="Your Discards"
+                riichiIndex={gameState.riichi_discard[0]}
               />
             </div>
             <div className="p-1 rounded-lg">
               <HandDisplay
                 tiles={gameState.hands[0]}
                 drawnTile={gameState.drawn_tile[0]}
+                melds={gameState.melds[0]}
                 title="Your Hand"
                 clickable={
                   gameState.current_player === "Player" &&
                   gameState.phase === "Discard"
                 }
                 onTileClick={handleDiscard}
+                hints={hints}
               />
+              {(gameState.furiten[0].discard || gameState.furiten[0].temporary || gameState.furiten[0].riichi) && (
+                <p className="text-sm font-semibold text-red-600">Furiten</p>
+              )}
+              {gameState.waits.length > 0 && (
+                <div className="flex gap-1 items-center text-sm">
+                  <strong>Waiting on:</strong>
+                  {gameState.waits.map((wait) => (
+                    <TileDisplay key={`wait-${wait.tile.id}`} tile={wait.tile} size="small" />
+                  ))}
+                  <span>({gameState.waits.reduce((left, wait) => left + wait.remaining, 0)} left)</span>
+                </div>
+              )}
               {/* Win buttons */}
               {gameState.current_player === "Player" && gameState.phase === "Discard" && (
                 <div className="mt-4 flex gap-2">
@@ -305,6 +522,39 @@ export function App() {
                       Tsumo
                     </button>
                   )}
+                  {gameState.can_riichi[0] && (
+                    <button
+                      onClick={() => setRiichiMode(riichiMode === "closed" ? null : "closed")}
+                      className={`${riichiMode === "closed" ? "bg-yellow-600" : "bg-yellow-500"} text-white px-4 py-2 rounded hover:bg-yellow-600`}
+                    >
+                      {riichiMode === "closed" ? "Choose Riichi Discard" : "Riichi"}
+                    </button>
+                  )}
+                  {gameState.can_riichi[0] && gameState.rules.open_riichi && (
+                    <button
+                      onClick={() => setRiichiMode(riichiMode === "open" ? null : "open")}
+                      className={`${riichiMode === "open" ? "bg-yellow-600" : "bg-yellow-500"} text-white px-4 py-2 rounded hover:bg-yellow-600`}
+                    >
+                      {riichiMode === "open" ? "Choose Open Riichi Discard" : "Open Riichi"}
+                    </button>
+                  )}
+                  {gameState.can_kyuushu[0] && (
+                    <button
+                      onClick={handleKyuushu}
+                      className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600"
+                    >
+                      Nine Terminals
+                    </button>
+                  )}
+                  {gameState.kan_options[0].map((option, index) => (
+                    <button
+                      key={`kan-${index}`}
+                      onClick={() => handleKan(option)}
+                      className="bg-orange-500 text-white px-4 py-2 rounded hover:bg-orange-600"
+                    >
+                      Kan
+                    </button>
+                  ))}
                 </div>
               )}
               {/* Ron phase buttons */}
@@ -326,6 +576,26 @@ export function App() {
                   </button>
                 </div>
               )}
+              {/* Call phase buttons */}
+              {gameState.current_player === "Player" && gameState.phase === "Call" && (
+                <div className="mt-4 flex gap-2">
+                  {gameState.call_options[0].map((option, index) => (
+                    <button
+                      key={`call-${index}`}
+                      onClick={() => handleCall(option)}
+                      className="bg-orange-500 text-white px-4 py-2 rounded hover:bg-orange-600"
+                    >
+                      {option.kind === "Daiminkan" ? "Kan" : option.kind}
+                    </button>
+                  ))}
+                  <button
+                    onClick={handlePass}
+                    className="bg-gray-500 text-white px-4 py-2 rounded hover:bg-gray-600"
+                  >
+                    Pass
+                  </button>
+                </div>
+              )}
             </div>
           </div>
         </div>
@@ -338,11 +608,16 @@ export function App() {
         onToggle={() => setDebugOpen(!debugOpen)}
       />
 
+      {replayOpen && (
+        <ReplayPanel gameOver={gameState?.phase === "End"} onClose={() => setReplayOpen(false)} />
+      )}
+
       {showEndModal && gameState && (
         <GameEndModal
           gameState={gameState}
           onClose={() => setShowEndModal(false)}
           onNewGame={startNewGame}
+          onNextHand={nextHand}
         />
       )}
     </div>
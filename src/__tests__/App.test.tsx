@@ -111,7 +111,7 @@ describe("App", () => {
     expect(tiles.length).toBeGreaterThan(0);
     await userEvent.click(tiles[0] as HTMLElement);
     await waitFor(() => {
-      expect(mockInvoke).toHaveBeenCalledWith("player_discard", expect.objectContaining({ tileId: expect.any(Number) }));
+      expect(mockInvoke).toHaveBeenCalledWith("player_discard", expect.objectContaining({ tile: expect.objectContaining({ id: expect.any(Number) }) }));
     });
   });
 
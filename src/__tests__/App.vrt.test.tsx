@@ -35,7 +35,7 @@ describe("App VRT", () => {
   it("game end modal structure", () => {
     const state = createMockGameState({ wall_count: 0, phase: "End" });
     const { container } = render(
-      <GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} />
+      <GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} onNextHand={() => {}} />
     );
     expect(container.querySelector(".fixed.inset-0")).toMatchSnapshot();
   });
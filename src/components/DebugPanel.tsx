@@ -1,12 +1,47 @@
-import { GameState } from "../types";
+import { useEffect, useState } from "preact/hooks";
+import { GameEvent, GameState, PlayerView } from "../types";
+import { safeInvoke } from "../lib/tauri";
+import { HandDisplay } from "./HandDisplay";
 
 interface DebugPanelProps {
-  gameState: GameState;
+  gameState: PlayerView;
   isOpen: boolean;
   onToggle: () => void;
 }
 
+const SEATS = ["Player", "Cpu1", "Cpu2", "Cpu3"];
+
+// Most recent events listed in the panel
+const RECENT_EVENTS = 10;
+
+function describeEvent(event: GameEvent): string {
+  const actor = "seat" in event ? event.seat : "winner" in event ? event.winner : null;
+  const tile = "tile" in event && event.tile ? ` ${event.tile.id}` : "";
+  return actor ? `${event.type} ${actor}${tile}` : event.type;
+}
+
 export function DebugPanel({ gameState, isOpen, onToggle }: DebugPanelProps) {
+  // The unmasked state is only fetched while the panel is open
+  const [fullState, setFullState] = useState<GameState | null>(null);
+  const [events, setEvents] = useState<GameEvent[]>([]);
+
+  useEffect(() => {
+    if (!isOpen) {
+      setFullState(null);
+      return;
+    }
+    safeInvoke<GameState>("get_debug_state", undefined, { showAlert: false }).then((state) => {
+      if (state) {
+        setFullState(state);
+      }
+    });
+    safeInvoke<GameEvent[]>("get_events", { since: 0 }, { showAlert: false }).then((log) => {
+      if (log) {
+        setEvents(log.slice(-RECENT_EVENTS));
+      }
+    });
+  }, [isOpen, gameState]);
+
   if (!isOpen) {
     return (
       <button
@@ -39,6 +74,9 @@ export function DebugPanel({ gameState, isOpen, onToggle }: DebugPanelProps) {
         <div>
           <strong>Wall Count:</strong> {gameState.wall_count}
         </div>
+        <div>
+          <strong>Seed:</strong> {fullState?.seed ?? "-"}
+        </div>
         <div>
           <strong>Can Tsumo:</strong> {gameState.can_tsumo.map((v, i) => `${i}:${v ? "Yes" : "No"}`).join(", ")}
         </div>
@@ -48,12 +86,36 @@ export function DebugPanel({ gameState, isOpen, onToggle }: DebugPanelProps) {
         <div className="mt-4">
           <strong>Hand Sizes:</strong>
           <ul className="list-disc list-inside ml-2">
-            <li>Player: {gameState.hands[0].length}</li>
-            <li>Cpu1: {gameState.hands[1].length}</li>
-            <li>Cpu2: {gameState.hands[2].length}</li>
-            <li>Cpu3: {gameState.hands[3].length}</li>
+            <li>Player: {gameState.hand_counts[0]}</li>
+            <li>Cpu1: {gameState.hand_counts[1]}</li>
+            <li>Cpu2: {gameState.hand_counts[2]}</li>
+            <li>Cpu3: {gameState.hand_counts[3]}</li>
           </ul>
         </div>
+        {events.length > 0 && (
+          <div className="mt-4">
+            <strong>Recent Events:</strong>
+            <ul className="list-disc list-inside ml-2">
+              {events.map((event, index) => (
+                <li key={index}>{describeEvent(event)}</li>
+              ))}
+            </ul>
+          </div>
+        )}
+        {fullState && (
+          <div className="mt-4">
+            <strong>Hands:</strong>
+            {fullState.hands.map((hand, index) => (
+              <HandDisplay
+                key={SEATS[index]}
+                tiles={hand}
+                drawnTile={fullState.drawn_tile[index]}
+                title={SEATS[index]}
+                melds={fullState.melds[index]}
+              />
+            ))}
+          </div>
+        )}
         <div className="mt-4">
           <strong>Discard Sizes:</strong>
           <ul className="list-disc list-inside ml-2">
@@ -5,15 +5,18 @@ interface DiscardDisplayProps {
   tiles: Tile[];
   title?: string;
   hidden?: boolean; // If true, show tiles face down (for CPU)
+  riichiIndex?: number | null; // Index of the sideways riichi tile
 }
 
-export function DiscardDisplay({ tiles, title, hidden = false }: DiscardDisplayProps) {
+export function DiscardDisplay({ tiles, title, hidden = false, riichiIndex = null }: DiscardDisplayProps) {
   return (
     <div className="mb-4">
       {title && <h3 className="text-sm font-semibold mb-2">{title}</h3>}
       <div className="discard-display-container">
         {tiles.map((tile, index) => (
-          <TileDisplay key={`${tile.id}-${index}`} tile={tile} size="small" hidden={hidden} />
+          <div key={`${tile.id}-${index}`} className={index === riichiIndex ? "riichi-discard" : ""}>
+            <TileDisplay tile={tile} size="small" hidden={hidden} />
+          </div>
         ))}
       </div>
     </div>
@@ -1,14 +1,43 @@
-import { GameState } from "../types";
+import { GameResult, GameState, Player } from "../types";
+
+const SEATS: Player[] = ["Player", "Cpu1", "Cpu2", "Cpu3"];
 
 interface GameEndModalProps {
   gameState: GameState;
   onClose: () => void;
   onNewGame: () => void;
+  onNextHand: () => void;
+}
+
+function describeResult(result: GameResult): string {
+  switch (result.win_type) {
+    case "Ron":
+      return `Ron by ${result.winner} (dealt in by ${result.deal_in})`;
+    case "Tsumo":
+      return `Tsumo by ${result.winner}`;
+    case "ExhaustiveDraw":
+      return "Wall Exhausted (流局)";
+    case "FourWinds":
+      return "Four Winds (四風連打)";
+    case "FourKans":
+      return "Four Kans (四開槓)";
+    case "TripleRon":
+      return "Triple Ron (三家和)";
+    case "NineTerminals":
+      return "Nine Terminals (九種九牌)";
+    case "FourRiichi":
+      return "Four Riichi (四家立直)";
+  }
 }
 
-export function GameEndModal({ gameState, onClose, onNewGame }: GameEndModalProps) {
+export function GameEndModal({ gameState, onClose, onNewGame, onNextHand }: GameEndModalProps) {
+  const matchState = gameState.match_state;
   const isWallExhausted = gameState.wall_count === 0;
-  const reason = isWallExhausted ? "Wall Exhausted (流局)" : "Game Ended";
+  const reason = gameState.result
+    ? describeResult(gameState.result)
+    : isWallExhausted
+      ? "Wall Exhausted (流局)"
+      : "Game Ended";
 
   return (
     <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
@@ -18,14 +47,59 @@ export function GameEndModal({ gameState, onClose, onNewGame }: GameEndModalProp
           <p className="text-center text-gray-700 mb-2">
             <strong>Reason:</strong> {reason}
           </p>
+          {gameState.result?.score && (
+            <p className="text-center text-gray-700 mb-2">
+              <strong>Hand:</strong> {gameState.result.score.yaku.join(", ")} ({gameState.result.score.han} han{" "}
+              {gameState.result.score.fu} fu)
+            </p>
+          )}
+          {gameState.result?.score && gameState.result.score.dora.dora + gameState.result.score.dora.ura_dora + gameState.result.score.dora.aka_dora > 0 && (
+            <p className="text-center text-gray-700 mb-2">
+              <strong>Dora:</strong> {gameState.result.score.dora.dora} / Ura {gameState.result.score.dora.ura_dora} / Aka{" "}
+              {gameState.result.score.dora.aka_dora}
+            </p>
+          )}
+          {gameState.additional_results.map((result, index) => (
+            <p key={`additional-${index}`} className="text-center text-gray-700 mb-2">
+              <strong>Also:</strong> {describeResult(result)}
+              {result.score && ` — ${result.score.yaku.join(", ")} (${result.score.han} han ${result.score.fu} fu)`}
+            </p>
+          ))}
+          {gameState.result?.win_type === "ExhaustiveDraw" && (
+            <p className="text-center text-gray-700 mb-2">
+              <strong>Tenpai:</strong>{" "}
+              {SEATS.filter((_, index) => gameState.result?.tenpai[index]).join(", ") || "None"}
+            </p>
+          )}
+          {gameState.result && gameState.result.nagashi_mangan.length > 0 && (
+            <p className="text-center text-gray-700 mb-2">
+              <strong>Nagashi Mangan:</strong> {gameState.result.nagashi_mangan.join(", ")}
+            </p>
+          )}
           <p className="text-center text-gray-700 mb-2">
             <strong>Wall Count:</strong> {gameState.wall_count}
           </p>
           <p className="text-center text-gray-700">
             <strong>Phase:</strong> {gameState.phase}
           </p>
+          {matchState?.finished && (
+            <p className="text-center text-gray-700 mt-2">
+              <strong>Final Ranking:</strong>{" "}
+              {matchState.ranking
+                .map((seat, place) => `${place + 1}. ${seat} (${gameState.scores[SEATS.indexOf(seat)]})`)
+                .join(", ")}
+            </p>
+          )}
         </div>
         <div className="flex gap-3 justify-center">
+          {matchState && !matchState.finished && (
+            <button
+              onClick={onNextHand}
+              className="bg-green-500 text-white px-6 py-2 rounded hover:bg-green-600"
+            >
+              Next Hand
+            </button>
+          )}
           <button
             onClick={onNewGame}
             className="bg-blue-500 text-white px-6 py-2 rounded hover:bg-blue-600"
@@ -1,5 +1,5 @@
 import { useMemo } from "preact/hooks";
-import { Tile } from "../types";
+import { DiscardOption, Meld, Tile } from "../types";
 import { TileDisplay } from "./TileDisplay";
 
 interface HandDisplayProps {
@@ -9,9 +9,28 @@ interface HandDisplayProps {
   onTileClick?: (tile: Tile) => void;
   clickable?: boolean;
   hidden?: boolean; // If true, show tiles face down (for CPU)
+  melds?: Meld[]; // Called melds, always shown face up
+  concealedCount?: number; // Face-down tiles to show when the tiles are masked out
+  hasDrawnTile?: boolean; // Show a face-down drawn tile when it is masked out
+  hints?: DiscardOption[]; // Discard analysis, best first, shown over each tile
 }
 
-export function HandDisplay({ tiles, drawnTile, title, onTileClick, clickable = false, hidden = false }: HandDisplayProps) {
+// Stands in for a masked tile; only its back is drawn
+const MASKED_TILE: Tile = { id: 0 };
+
+export function HandDisplay({
+  tiles,
+  drawnTile,
+  title,
+  onTileClick,
+  clickable = false,
+  hidden = false,
+  melds = [],
+  concealedCount = 0,
+  hasDrawnTile = false,
+  hints = [],
+}: HandDisplayProps) {
+  const maskedCount = tiles.length === 0 ? concealedCount : 0;
   // Generate unique keys using timestamp + index to ensure stable rendering
   // Use useMemo to generate keys only when tiles array changes
   const tileKeys = useMemo(() => {
@@ -19,6 +38,21 @@ export function HandDisplay({ tiles, drawnTile, title, onTileClick, clickable =
     return tiles.map((tile, index) => `${tile.id}-${timestamp}-${index}`);
   }, [tiles]);
   
+  // Shanten and improving tile count over a tile, with the best discards highlighted
+  const renderHint = (tile: Tile) => {
+    const hint = hints.find((option) => option.tile.id === tile.id);
+    if (!hint) return null;
+    const best = hint.shanten === hints[0].shanten && hint.ukeire_count === hints[0].ukeire_count;
+    return (
+      <div
+        className={`discard-hint text-[10px] text-center rounded ${best ? "bg-green-200 font-bold" : "bg-gray-100"}`}
+        title={`Shanten ${hint.shanten}, ${hint.ukeire_count} improving tiles`}
+      >
+        {hint.shanten}/{hint.ukeire_count}
+      </div>
+    );
+  };
+
   return (
     <div className="mb-4">
       {title && <h3 className="text-sm font-semibold mb-2">{title}</h3>}
@@ -29,9 +63,15 @@ export function HandDisplay({ tiles, drawnTile, title, onTileClick, clickable =
             onClick={() => clickable && onTileClick?.(tile)}
             className={clickable ? "cursor-pointer hover:opacity-70 transition-opacity" : ""}
           >
+            {renderHint(tile)}
             <TileDisplay tile={tile} size="medium" hidden={hidden} />
           </div>
         ))}
+        {Array.from({ length: maskedCount }, (_, index) => (
+          <div key={`masked-${index}`}>
+            <TileDisplay tile={MASKED_TILE} size="medium" hidden />
+          </div>
+        ))}
         {/* Display drawn tile with spacing */}
         {drawnTile && (
           <div className="drawn-tile-wrapper">
@@ -39,10 +79,24 @@ export function HandDisplay({ tiles, drawnTile, title, onTileClick, clickable =
               onClick={() => clickable && onTileClick?.(drawnTile)}
               className={clickable ? "cursor-pointer hover:opacity-70 transition-opacity" : ""}
             >
+              {renderHint(drawnTile)}
               <TileDisplay tile={drawnTile} size="medium" hidden={hidden} />
             </div>
           </div>
         )}
+        {!drawnTile && hasDrawnTile && (
+          <div className="drawn-tile-wrapper">
+            <TileDisplay tile={MASKED_TILE} size="medium" hidden />
+          </div>
+        )}
+        {/* Called melds to the right of the hand */}
+        {melds.map((meld, meldIndex) => (
+          <div key={`meld-${meldIndex}`} className="meld-wrapper flex ml-2">
+            {meld.tiles.map((tile, index) => (
+              <TileDisplay key={`${tile.id}-${index}`} tile={tile} size="medium" />
+            ))}
+          </div>
+        ))}
       </div>
     </div>
   );
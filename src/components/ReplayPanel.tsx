@@ -0,0 +1,92 @@
+import { useState } from "preact/hooks";
+import { GameRecord, Player, ReplayFrame, ReplaySeek } from "../types";
+import { safeInvoke } from "../lib/tauri";
+import { HandDisplay } from "./HandDisplay";
+import { DiscardDisplay } from "./DiscardDisplay";
+
+const SEATS: Player[] = ["Player", "Cpu1", "Cpu2", "Cpu3"];
+
+interface ReplayPanelProps {
+  /** The record is only handed out once the game is over */
+  gameOver: boolean;
+  onClose: () => void;
+}
+
+export function ReplayPanel({ gameOver, onClose }: ReplayPanelProps) {
+  const [frame, setFrame] = useState<ReplayFrame | null>(null);
+
+  // Replays the finished game from its record
+  const loadCurrentGame = async () => {
+    const record = await safeInvoke<GameRecord>("get_record");
+    if (!record) return;
+    const loaded = await safeInvoke<ReplayFrame>("replay_load", { record });
+    if (loaded) {
+      setFrame(loaded);
+    }
+  };
+
+  const seek = async (target: ReplaySeek) => {
+    const next = await safeInvoke<ReplayFrame>("replay_seek", { seek: target });
+    if (next) {
+      setFrame(next);
+    }
+  };
+
+  const button = (label: string, target: ReplaySeek) => (
+    <button
+      onClick={() => seek(target)}
+      className="bg-gray-500 text-white px-3 py-1 rounded hover:bg-gray-600 text-sm"
+    >
+      {label}
+    </button>
+  );
+
+  return (
+    <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
+      <div className="bg-white rounded-lg p-4 max-w-3xl w-full mx-4 shadow-xl max-h-[90vh] overflow-auto">
+        <div className="flex justify-between items-center mb-2">
+          <h2 className="text-lg font-bold">Replay</h2>
+          <button onClick={onClose} className="text-gray-500 hover:text-black">
+            ✕
+          </button>
+        </div>
+        <div className="flex gap-2 items-center mb-2">
+          <button
+            onClick={loadCurrentGame}
+            disabled={!gameOver}
+            className="bg-blue-500 text-white px-3 py-1 rounded hover:bg-blue-600 text-sm disabled:opacity-50"
+          >
+            Load Finished Game
+          </button>
+          {!gameOver && <span className="text-sm text-gray-500">Available once the game is over</span>}
+          {frame && (
+            <>
+              {button("« Turn", "PreviousTurn")}
+              {button("‹ Action", "PreviousAction")}
+              {button("Action ›", "NextAction")}
+              {button("Turn »", "NextTurn")}
+              <span className="text-sm">
+                {frame.position} / {frame.length}
+              </span>
+            </>
+          )}
+        </div>
+        {frame &&
+          SEATS.map((seat, index) => (
+            <div key={seat}>
+              <HandDisplay
+                tiles={frame.state.hands[index]}
+                drawnTile={frame.state.drawn_tile[index]}
+                melds={frame.state.melds[index]}
+                title={seat}
+              />
+              <DiscardDisplay
+                tiles={frame.state.discards[index]}
+                riichiIndex={frame.state.riichi_discard[index]}
+              />
+            </div>
+          ))}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,69 @@
+import { useState } from "preact/hooks";
+import { AbortiveDraws, RedFives, RuleConfig } from "../types";
+import { createDefaultRules } from "../lib/rules";
+
+interface RuleSettingsProps {
+  /** Starts a single hand; match length only applies to matches */
+  onStart: (rules: RuleConfig) => void;
+}
+
+const ABORTIVE_DRAW_LABELS: [keyof AbortiveDraws, string][] = [
+  ["four_winds", "Four winds"],
+  ["four_kans", "Four kans"],
+  ["triple_ron", "Triple ron"],
+  ["nine_terminals", "Nine terminals"],
+  ["four_riichi", "Four riichi"],
+];
+
+export function RuleSettings({ onStart }: RuleSettingsProps) {
+  const [rules, setRules] = useState<RuleConfig>(createDefaultRules());
+  const update = (changes: Partial<RuleConfig>) => setRules({ ...rules, ...changes });
+
+  const checkbox = (label: string, checked: boolean, onChange: (checked: boolean) => void) => (
+    <label className="text-sm flex items-center gap-2 cursor-pointer">
+      <input type="checkbox" checked={checked} onChange={(e) => onChange(e.currentTarget.checked)} />
+      {label}
+    </label>
+  );
+
+  return (
+    <div className="flex flex-col gap-1 text-left">
+      <label className="text-sm flex items-center gap-2">
+        Red fives
+        <select value={rules.red_fives} onChange={(e) => update({ red_fives: e.currentTarget.value as RedFives })}>
+          <option value="Zero">0</option>
+          <option value="Three">3</option>
+          <option value="Four">4</option>
+        </select>
+      </label>
+      <label className="text-sm flex items-center gap-2">
+        Starting points
+        <input
+          type="number"
+          step={100}
+          className="w-24"
+          value={rules.starting_points}
+          onChange={(e) => update({ starting_points: Number(e.currentTarget.value) })}
+        />
+      </label>
+      {checkbox("Open tanyao", rules.kuitan, (kuitan) => update({ kuitan }))}
+      {checkbox("Double ron", rules.ron_resolution === "DoubleRon", (double) =>
+        update({ ron_resolution: double ? "DoubleRon" : "HeadBump" })
+      )}
+      {checkbox("Busting", rules.busting, (busting) => update({ busting }))}
+      {checkbox("Open riichi", rules.open_riichi, (open_riichi) => update({ open_riichi }))}
+      {checkbox("Practice (undo discards)", rules.practice, (practice) => update({ practice }))}
+      {ABORTIVE_DRAW_LABELS.map(([key, label]) =>
+        checkbox(label, rules.abortive_draws[key], (enabled) =>
+          update({ abortive_draws: { ...rules.abortive_draws, [key]: enabled } })
+        )
+      )}
+      <button
+        onClick={() => onStart(rules)}
+        className="bg-blue-500 text-white px-4 py-2 rounded hover:bg-blue-600 text-sm mt-1"
+      >
+        Start With Rules
+      </button>
+    </div>
+  );
+}
@@ -399,7 +399,7 @@ export function TileDisplay({ tile, size = "medium", hidden = false }: TileDispl
   const sizeDataAttr = size || "medium";
   
   return (
-    <div className={`tile-3d-container ${sizeClass}`} title={hidden ? "Hidden tile" : `Tile ID: ${tile.id}`} data-size={sizeDataAttr}>
+    <div className={`tile-3d-container ${sizeClass}`} title={hidden ? "Hidden tile" : `Tile ID: ${tile.id}${tile.red ? " (red)" : ""}`} data-size={sizeDataAttr}>
       <div className={`tile-3d ${sizeClass} ${depthClass}`}>
         {/* Front face */}
         <div className="tile-face tile-front">
@@ -408,7 +408,7 @@ export function TileDisplay({ tile, size = "medium", hidden = false }: TileDispl
               <div className="tile-back-pattern"></div>
             </div>
           ) : (
-            <div className={`tile-surface border border-gray-400 rounded bg-white flex items-center justify-center`} data-size={sizeDataAttr}>
+            <div className={`tile-surface border border-gray-400 rounded bg-white flex items-center justify-center${tile.red ? " tile-red-five" : ""}`} data-size={sizeDataAttr}>
               {renderTileContent()}
             </div>
           )}
@@ -7,21 +7,63 @@ import { createMockGameState } from "../../test/tauri-mock";
 describe("GameEndModal", () => {
   it("shows Wall Exhausted when wall_count is 0", () => {
     const state = createMockGameState({ wall_count: 0, phase: "End" });
-    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} />);
+    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} onNextHand={() => {}} />);
     expect(screen.getByText(/Wall Exhausted/)).toBeInTheDocument();
   });
 
   it("shows Game Ended when wall_count is not 0", () => {
     const state = createMockGameState({ wall_count: 10, phase: "End" });
-    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} />);
+    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} onNextHand={() => {}} />);
     expect(screen.getByRole("heading", { name: "Game Ended" })).toBeInTheDocument();
   });
 
+  it("shows the recorded ron result", () => {
+    const state = createMockGameState({
+      wall_count: 10,
+      phase: "End",
+      result: {
+        winner: "Cpu2",
+        win_type: "Ron",
+        winning_tile: { id: 27 },
+        deal_in: "Player",
+        score: null,
+        point_changes: [0, 0, 0, 0],
+        ura_dora_indicators: [],
+        tenpai: [false, false, false, false],
+        dealer_tenpai: false,
+        nagashi_mangan: [],
+      },
+    });
+    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} onNextHand={() => {}} />);
+    expect(screen.getByText(/Ron by Cpu2 \(dealt in by Player\)/)).toBeInTheDocument();
+  });
+
+  it("lists tenpai seats after an exhaustive draw", () => {
+    const state = createMockGameState({
+      wall_count: 0,
+      phase: "End",
+      result: {
+        winner: null,
+        win_type: "ExhaustiveDraw",
+        winning_tile: null,
+        deal_in: null,
+        score: null,
+        point_changes: [1500, -1500, 1500, -1500],
+        ura_dora_indicators: [],
+        tenpai: [true, false, true, false],
+        dealer_tenpai: true,
+        nagashi_mangan: [],
+      },
+    });
+    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} onNextHand={() => {}} />);
+    expect(screen.getByText(/Player, Cpu2/)).toBeInTheDocument();
+  });
+
   it("calls onNewGame when New Game button is clicked", async () => {
     expect.assertions(1);
     const state = createMockGameState({ wall_count: 0 });
     const onNewGame = vi.fn();
-    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={onNewGame} />);
+    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={onNewGame} onNextHand={() => {}} />);
     await userEvent.click(screen.getByRole("button", { name: /New Game/i }));
     expect(onNewGame).toHaveBeenCalledTimes(1);
   });
@@ -30,8 +72,29 @@ describe("GameEndModal", () => {
     expect.assertions(1);
     const state = createMockGameState({ wall_count: 0 });
     const onClose = vi.fn();
-    render(<GameEndModal gameState={state} onClose={onClose} onNewGame={() => {}} />);
+    render(<GameEndModal gameState={state} onClose={onClose} onNewGame={() => {}} onNextHand={() => {}} />);
     await userEvent.click(screen.getByRole("button", { name: /Close/i }));
     expect(onClose).toHaveBeenCalledTimes(1);
   });
+
+  it("calls onNextHand during a match", async () => {
+    expect.assertions(1);
+    const state = createMockGameState({
+      phase: "End",
+      match_state: {
+        length: "EastOnly",
+        round_wind: "East",
+        first_dealer: "Player",
+        dealer: "Player",
+        hand_number: 1,
+        honba: 0,
+        finished: false,
+        ranking: [],
+      },
+    });
+    const onNextHand = vi.fn();
+    render(<GameEndModal gameState={state} onClose={() => {}} onNewGame={() => {}} onNextHand={onNextHand} />);
+    await userEvent.click(screen.getByRole("button", { name: /Next Hand/i }));
+    expect(onNextHand).toHaveBeenCalledTimes(1);
+  });
 });
@@ -49,4 +49,23 @@ describe("HandDisplay", () => {
     render(<HandDisplay tiles={[{ id: 0 }]} title="Your Hand" />);
     expect(screen.getByText("Your Hand")).toBeInTheDocument();
   });
+
+  it("shows face-down tiles for a masked hand", () => {
+    const { container } = render(<HandDisplay tiles={[]} concealedCount={13} hasDrawnTile />);
+    expect(container.querySelectorAll('[title="Hidden tile"]').length).toBe(14);
+    expect(container.querySelector(".drawn-tile-wrapper")).toBeInTheDocument();
+  });
+
+  it("shows discard hints over matching tiles and marks the best", () => {
+    const hints = [
+      { tile: { id: 5 }, shanten: 0, ukeire: [{ tile: { id: 2 }, remaining: 4 }], ukeire_count: 4 },
+      { tile: { id: 0 }, shanten: 1, ukeire: [], ukeire_count: 12 },
+    ];
+    const { container } = render(<HandDisplay tiles={[{ id: 0 }, { id: 1 }]} drawnTile={{ id: 5 }} hints={hints} />);
+    const shown = container.querySelectorAll(".discard-hint");
+    expect(shown.length).toBe(2);
+    expect(shown[0].textContent).toBe("1/12");
+    expect(shown[1].textContent).toBe("0/4");
+    expect(shown[1].className).toContain("font-bold");
+  });
 });
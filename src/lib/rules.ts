@@ -0,0 +1,24 @@
+import type { RuleConfig } from "../types";
+
+/**
+ * Default rule set, matching RuleConfig::default() on the Rust side.
+ */
+export function createDefaultRules(): RuleConfig {
+  return {
+    red_fives: "Three",
+    kuitan: true,
+    ron_resolution: "HeadBump",
+    abortive_draws: {
+      four_winds: true,
+      four_kans: true,
+      triple_ron: true,
+      nine_terminals: true,
+      four_riichi: true,
+    },
+    starting_points: 25000,
+    match_length: "EastSouth",
+    busting: true,
+    open_riichi: false,
+    practice: false,
+  };
+}
@@ -1,4 +1,5 @@
 import type { GameState, Tile } from "../types";
+import { createDefaultRules } from "../lib/rules";
 
 /**
  * Creates a mock GameState for tests. Override any field via overrides.
@@ -17,6 +18,29 @@ export function createMockGameState(overrides: Partial<GameState> = {}): GameSta
     can_ron: [false, false, false, false],
     drawn_tile: [null, null, null, null],
     last_discarder: null,
+    melds: [[], [], [], []],
+    call_options: [[], [], [], []],
+    kan_options: [[], [], [], []],
+    can_riichi: [false, false, false, false],
+    can_kyuushu: [false, false, false, false],
+    riichi: [false, false, false, false],
+    double_riichi: [false, false, false, false],
+    open_riichi: [false, false, false, false],
+    ippatsu: [false, false, false, false],
+    riichi_discard: [null, null, null, null],
+    riichi_sticks: 0,
+    furiten: [0, 1, 2, 3].map(() => ({ discard: false, temporary: false, riichi: false })),
+    waits: [],
+    result: null,
+    additional_results: [],
+    dora_indicators: [],
+    scores: [25000, 25000, 25000, 25000],
+    dealer: "Player",
+    round_wind: "East",
+    honba: 0,
+    match_state: null,
+    rules: createDefaultRules(),
+    seed: 0,
     ...overrides,
   };
 }
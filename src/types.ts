@@ -1,10 +1,126 @@
 // Type definitions matching Rust backend
 
 export type Player = "Player" | "Cpu1" | "Cpu2" | "Cpu3";
-export type GamePhase = "Draw" | "Discard" | "Ron" | "End";
+export type GamePhase = "Draw" | "Discard" | "Ron" | "Call" | "End";
+export type WinType =
+  | "Ron"
+  | "Tsumo"
+  | "ExhaustiveDraw"
+  | "FourWinds"
+  | "FourKans"
+  | "TripleRon"
+  | "NineTerminals"
+  | "FourRiichi";
 
 export interface Tile {
   id: number; // 0-33
+  red?: boolean; // Red five (aka-dora)
+}
+
+export type MeldKind = "Chi" | "Pon" | "Daiminkan" | "Ankan" | "Shouminkan";
+
+export interface Meld {
+  kind: MeldKind;
+  tiles: Tile[];
+  called_tile: Tile | null;
+  from: Player | null; // Seat whose discard was called
+}
+
+export interface CallOption {
+  kind: MeldKind;
+  tiles: Tile[]; // Tiles from hand used for the call or kan
+}
+
+export interface Wait {
+  tile: Tile;
+  remaining: number; // Copies the player has not seen
+}
+
+export interface DiscardOption {
+  tile: Tile;
+  shanten: number; // Shanten left after this discard; 0 is tenpai
+  ukeire: Wait[]; // Tiles that would lower the shanten, with unseen copies
+  ukeire_count: number;
+}
+
+export interface Furiten {
+  discard: boolean; // A wait is in the seat's own discards
+  temporary: boolean; // A winning tile went by since the seat's last discard
+  riichi: boolean; // A winning tile went by after riichi
+}
+
+export type Wind = "East" | "South" | "West" | "North";
+
+export type LimitHand = "Mangan" | "Haneman" | "Baiman" | "Sanbaiman" | "KazoeYakuman" | "Yakuman";
+
+export type Payment =
+  | { Ron: { total: number } }
+  | { Tsumo: { dealer: number; non_dealer: number } };
+
+export interface DoraCount {
+  dora: number;
+  ura_dora: number; // Riichi hands only
+  aka_dora: number; // Red fives
+}
+
+export interface ScoreResult {
+  yaku: string[];
+  han: number;
+  fu: number;
+  yakuman: number;
+  dora: DoraCount;
+  limit: LimitHand | null;
+  base_points: number;
+  payment: Payment;
+}
+
+export interface GameResult {
+  winner: Player | null; // null for a draw
+  win_type: WinType;
+  winning_tile: Tile | null;
+  deal_in: Player | null; // Player whose discard was ronned
+  score: ScoreResult | null; // null for a draw
+  point_changes: number[]; // [Player, Cpu1, Cpu2, Cpu3]
+  ura_dora_indicators: Tile[]; // Revealed when a riichi hand wins
+  tenpai: boolean[]; // Seats tenpai at an exhaustive draw
+  dealer_tenpai: boolean;
+  nagashi_mangan: Player[];
+}
+
+export type MatchLength = "EastOnly" | "EastSouth";
+
+export interface MatchState {
+  length: MatchLength;
+  round_wind: Wind;
+  first_dealer: Player;
+  dealer: Player;
+  hand_number: number; // Hand within the round, e.g. East 2
+  honba: number;
+  finished: boolean;
+  ranking: Player[]; // First to last place once finished
+}
+
+export type RedFives = "Zero" | "Three" | "Four";
+export type RonResolution = "HeadBump" | "DoubleRon";
+
+export interface AbortiveDraws {
+  four_winds: boolean;
+  four_kans: boolean;
+  triple_ron: boolean;
+  nine_terminals: boolean;
+  four_riichi: boolean;
+}
+
+export interface RuleConfig {
+  red_fives: RedFives;
+  kuitan: boolean; // Open tanyao
+  ron_resolution: RonResolution;
+  abortive_draws: AbortiveDraws;
+  starting_points: number;
+  match_length: MatchLength;
+  busting: boolean; // Match ends when a seat drops below zero
+  open_riichi: boolean;
+  practice: boolean; // The player may take back discards
 }
 
 export interface GameState {
@@ -17,5 +133,86 @@ export interface GameState {
   can_ron: boolean[]; // [Player, Cpu1, Cpu2, Cpu3]
   drawn_tile: (Tile | null)[]; // [Player, Cpu1, Cpu2, Cpu3]
   last_discarder: Player | null; // Last player who discarded a tile
+  melds: Meld[][]; // [Player, Cpu1, Cpu2, Cpu3]
+  call_options: CallOption[][]; // Calls available on the last discard
+  kan_options: CallOption[][]; // Closed/added kans the current player may declare
+  can_riichi: boolean[]; // [Player, Cpu1, Cpu2, Cpu3]
+  can_kyuushu: boolean[]; // Nine terminals may be declared [Player, Cpu1, Cpu2, Cpu3]
+  riichi: boolean[]; // [Player, Cpu1, Cpu2, Cpu3]
+  double_riichi: boolean[];
+  open_riichi: boolean[]; // Riichi declared with the hand shown
+  ippatsu: boolean[];
+  riichi_discard: (number | null)[]; // Index of each seat's sideways discard
+  riichi_sticks: number; // Sticks on the table for the next winner
+  furiten: Furiten[]; // [Player, Cpu1, Cpu2, Cpu3]
+  waits: Wait[]; // The player's winning tiles; empty unless tenpai
+  result: GameResult | null; // Outcome of the hand once phase is End
+  additional_results: GameResult[]; // Further winners on the same discard (double ron)
+  dora_indicators: Tile[]; // Face-up indicators on the dead wall
+  scores: number[]; // [Player, Cpu1, Cpu2, Cpu3]
+  dealer: Player;
+  round_wind: Wind;
+  honba: number;
+  match_state: MatchState | null; // null for a standalone hand
+  rules: RuleConfig;
+  seed: number; // Same seed and inputs replay the same game; 0 in a view until the game is over
 }
 
+
+/** GameState as the player's seat sees it; opponents' concealed tiles are masked out */
+export interface PlayerView extends GameState {
+  seat: Player;
+  hand_counts: number[]; // Concealed tiles per seat, not counting a drawn tile
+  has_drawn_tile: boolean[];
+}
+
+/** One recorded action at the table; other seats' drawn tiles come back as null */
+export type GameEvent =
+  | { type: "Deal"; dealer: Player; round_wind: Wind; honba: number }
+  | { type: "Draw"; seat: Player; tile: Tile | null; rinshan: boolean }
+  | { type: "Discard"; seat: Player; tile: Tile; tsumogiri: boolean }
+  | { type: "Call"; seat: Player; kind: MeldKind; tiles: Tile[]; from: Player | null }
+  | { type: "Riichi"; seat: Player; open: boolean }
+  | { type: "Ron"; winner: Player; from: Player; tile: Tile }
+  | { type: "Tsumo"; winner: Player; tile: Tile }
+  | { type: "DoraReveal"; indicator: Tile }
+  | { type: "ExhaustiveDraw"; tenpai: boolean[] }
+  | { type: "Abort"; kind: WinType }
+  | { type: "Score"; point_changes: number[]; scores: number[] };
+
+/** An input to the game, as kept in a GameRecord */
+export type Action =
+  | { type: "PlayerDraw" }
+  | { type: "PlayerDiscard"; tile: Tile }
+  | { type: "PlayerRiichi"; tile: Tile }
+  | { type: "PlayerOpenRiichi"; tile: Tile }
+  | { type: "PlayerRon" }
+  | { type: "PlayerTsumo" }
+  | { type: "PlayerPon" }
+  | { type: "PlayerChi"; tiles: Tile[] }
+  | { type: "PlayerDaiminkan" }
+  | { type: "PlayerKan"; tile: Tile }
+  | { type: "PlayerKyuushu" }
+  | { type: "PlayerPass" }
+  | { type: "CpuStep" }
+  | { type: "NextHand" };
+
+export interface GameRecord {
+  seed: number;
+  rules: RuleConfig;
+  is_match: boolean;
+  actions: Action[];
+}
+
+export type ReplaySeek =
+  | { Position: number }
+  | "NextAction"
+  | "PreviousAction"
+  | "NextTurn"
+  | "PreviousTurn";
+
+export interface ReplayFrame {
+  position: number; // Actions applied so far
+  length: number;
+  state: GameState;
+}